@@ -0,0 +1,263 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, attr, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, SubMsg, WasmMsg, Addr};
+use cw2::set_contract_version;
+use pagination::filtered_page;
+use versioning::{assert_migration, migration_attributes};
+
+use presale::msg::{GetSaleStatusResponse, InstantiateMsg as PresaleInstantiateMsg, QueryMsg as PresaleQueryMsg};
+use presale::state::SaleStatus;
+use vesting::msg::InstantiateMsg as VestingInstantiateMsg;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, LaunchResponse, LaunchesResponse, MigrateMsg, QueryMsg};
+use crate::state::{CONFIG, INSTANTIATE_PRESALE_REPLY_ID, INSTANTIATE_VESTING_REPLY_ID, LAUNCHES, LAUNCH_COUNT, PENDING_LAUNCH, Config, Launch, PendingLaunch};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        presale_code_id: msg.presale_code_id,
+        vesting_code_id: msg.vesting_code_id,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    LAUNCH_COUNT.save(deps.storage, &0u64)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION)))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            owner,
+            presale_code_id,
+            vesting_code_id,
+        } => execute_update_config(deps, info, owner, presale_code_id, vesting_code_id),
+
+        ExecuteMsg::Launch {
+            project_owner,
+            label,
+            vesting,
+            presale,
+        } => execute_launch(deps, info, project_owner, label, vesting, *presale),
+
+    }
+}
+
+pub fn execute_update_config(deps: DepsMut, info: MessageInfo, owner: Option<String>, presale_code_id: Option<u64>, vesting_code_id: Option<u64>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+    if let Some(presale_code_id) = presale_code_id {
+        config.presale_code_id = presale_code_id;
+    }
+    if let Some(vesting_code_id) = vesting_code_id {
+        config.vesting_code_id = vesting_code_id;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn execute_launch(deps: DepsMut, info: MessageInfo, project_owner: String, label: String, vesting: VestingInstantiateMsg, presale: PresaleInstantiateMsg) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the launched presale always vests through the launched vesting contract
+    if label.is_empty() || presale.claim_start_time.is_some() {
+        return Err(ContractError::InvalidInput {});
+    }
+    if PENDING_LAUNCH.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::LaunchInProgress {});
+    }
+    PENDING_LAUNCH.save(deps.storage, &PendingLaunch {
+        project_owner: deps.api.addr_canonicalize(&project_owner)?,
+        label: label.clone(),
+        presale,
+        vesting: None,
+    })?;
+
+    // the presale is instantiated once the vesting address is known
+    let admin = deps.api.addr_humanize(&config.owner)?;
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(WasmMsg::Instantiate {
+            admin: Some(admin.to_string()),
+            code_id: config.vesting_code_id,
+            msg: to_binary(&vesting)?,
+            funds: vec![],
+            label: format!("{} vesting", label),
+        }, INSTANTIATE_VESTING_REPLY_ID))
+        .add_attributes(vec![
+            attr("action", "launch"),
+            attr("project_owner", project_owner),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_VESTING_REPLY_ID => reply_instantiate_vesting(deps, msg),
+        INSTANTIATE_PRESALE_REPLY_ID => reply_instantiate_presale(deps, env, msg),
+        _ => Err(ContractError::InvalidReply {}),
+    }
+}
+
+/// Address of the contract instantiated by the submessage, from its instantiate event.
+fn instantiated_address(api: &dyn Api, msg: Reply) -> Result<Addr, ContractError> {
+    let response = msg.result.into_result().map_err(|_| ContractError::InvalidReply {})?;
+    let address = response.events.iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == "_contract_address")
+        .ok_or(ContractError::InvalidReply {})?;
+    Ok(api.addr_validate(&address.value)?)
+}
+
+fn reply_instantiate_vesting(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pending = PENDING_LAUNCH.load(deps.storage)?;
+
+    let vesting = instantiated_address(deps.api, msg)?;
+    pending.presale.vesting = Some(vesting.to_string());
+    pending.vesting = Some(deps.api.addr_canonicalize(vesting.as_str())?);
+    PENDING_LAUNCH.save(deps.storage, &pending)?;
+
+    let admin = deps.api.addr_humanize(&config.owner)?;
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(WasmMsg::Instantiate {
+            admin: Some(admin.to_string()),
+            code_id: config.presale_code_id,
+            msg: to_binary(&pending.presale)?,
+            funds: vec![],
+            label: format!("{} presale", pending.label),
+        }, INSTANTIATE_PRESALE_REPLY_ID))
+        .add_attribute("vesting", vesting))
+}
+
+fn reply_instantiate_presale(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let pending = PENDING_LAUNCH.load(deps.storage)?;
+    let vesting = deps.api.addr_humanize(&pending.vesting.ok_or(ContractError::InvalidReply {})?)?;
+    let presale = instantiated_address(deps.api, msg)?;
+    let project_owner = deps.api.addr_humanize(&pending.project_owner)?;
+
+    let launch_id = LAUNCH_COUNT.load(deps.storage)?;
+    LAUNCHES.save(deps.storage, launch_id, &Launch {
+        project_owner: pending.project_owner,
+        presale: deps.api.addr_canonicalize(presale.as_str())?,
+        vesting: deps.api.addr_canonicalize(vesting.as_str())?,
+        created_at: env.block.time.seconds(),
+    })?;
+    LAUNCH_COUNT.save(deps.storage, &(launch_id + 1))?;
+    PENDING_LAUNCH.remove(deps.storage);
+
+    // the factory owns both contracts until now
+    let messages: Vec<CosmosMsg> = vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: vesting.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::SetWorker { worker: presale.to_string() })?,
+            funds: vec![],
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: vesting.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::TransferOwnerShip { new_owner: project_owner.to_string() })?,
+            funds: vec![],
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: presale.to_string(),
+            msg: to_binary(&presale::msg::ExecuteMsg::TransferOwnerShip { new_owner: project_owner.to_string() })?,
+            funds: vec![],
+        }),
+    ];
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("launch_id", launch_id.to_string()),
+            attr("presale", presale),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Launch { launch_id } => to_binary(&query_launch(deps, launch_id)?),
+        QueryMsg::Launches { status, start_after, limit } => to_binary(&query_launches(deps, status, start_after, limit)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        presale_code_id: config.presale_code_id,
+        vesting_code_id: config.vesting_code_id,
+    })
+}
+
+fn launch_response(deps: Deps, launch_id: u64, launch: Launch) -> StdResult<LaunchResponse> {
+    let presale = deps.api.addr_humanize(&launch.presale)?;
+    let sale_status: GetSaleStatusResponse = deps.querier.query_wasm_smart(&presale, &PresaleQueryMsg::GetSaleStatus {})?;
+    Ok(LaunchResponse {
+        launch_id,
+        project_owner: deps.api.addr_humanize(&launch.project_owner)?.to_string(),
+        presale: presale.to_string(),
+        vesting: deps.api.addr_humanize(&launch.vesting)?.to_string(),
+        status: sale_status.status,
+        created_at: launch.created_at,
+    })
+}
+
+fn query_launch(deps: Deps, launch_id: u64) -> StdResult<LaunchResponse> {
+    let launch = LAUNCHES.load(deps.storage, launch_id)?;
+    launch_response(deps, launch_id, launch)
+}
+
+fn query_launches(deps: Deps, status: Option<SaleStatus>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<LaunchesResponse> {
+    let (launches, last_launch_id) = filtered_page(
+        deps.storage,
+        &LAUNCHES,
+        start_after,
+        limit,
+        |launch_id, launch| launch_response(deps, launch_id, launch),
+        |launch| status.is_none() || status.as_ref() == Some(&launch.status),
+    )?;
+    Ok(LaunchesResponse { launches, last_launch_id })
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+use versioning::MigrationError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid input")]
+    InvalidInput {},
+
+    #[error("Launch In Progress")]
+    LaunchInProgress {},
+
+    #[error("Invalid Reply")]
+    InvalidReply {},
+
+    #[error("{0}")]
+    Migration(#[from] MigrationError),
+}
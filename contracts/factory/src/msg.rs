@@ -0,0 +1,75 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use presale::msg::InstantiateMsg as PresaleInstantiateMsg;
+use presale::state::SaleStatus;
+use vesting::msg::InstantiateMsg as VestingInstantiateMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub presale_code_id: u64,
+    pub vesting_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        presale_code_id: Option<u64>,
+        vesting_code_id: Option<u64>,
+    },
+    /// Instantiates a vesting and a presale, sets the presale as the vesting worker
+    /// and transfers the ownership of both contracts to the project owner.
+    /// The vesting of the presale message is replaced by the instantiated vesting contract.
+    Launch {
+        project_owner: String,
+        /// Prefix of the contract labels.
+        label: String,
+        vesting: VestingInstantiateMsg,
+        presale: Box<PresaleInstantiateMsg>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Launch {
+        launch_id: u64,
+    },
+    /// Launches in launch order with the given status when set, examining at most 30 launches per page.
+    Launches {
+        status: Option<SaleStatus>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub presale_code_id: u64,
+    pub vesting_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LaunchResponse {
+    pub launch_id: u64,
+    pub project_owner: String,
+    pub presale: String,
+    pub vesting: String,
+    /// Sale status queried from the presale.
+    pub status: SaleStatus,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LaunchesResponse {
+    pub launches: Vec<LaunchResponse>,
+    /// Last launch examined, the start_after of the next page.
+    pub last_launch_id: Option<u64>,
+}
@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::CanonicalAddr;
+use cw_storage_plus::{Item, Map};
+use presale::msg::InstantiateMsg as PresaleInstantiateMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    // Owner address, the only one allowed to launch projects.
+    pub owner: CanonicalAddr,
+    // Code id of the presale contracts.
+    pub presale_code_id: u64,
+    // Code id of the vesting contracts.
+    pub vesting_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Launch {
+    // Project owner, owning the presale and the vesting once launched.
+    pub project_owner: CanonicalAddr,
+    // Presale contract.
+    pub presale: CanonicalAddr,
+    // Vesting contract, the presale is its worker.
+    pub vesting: CanonicalAddr,
+    // Launch time.
+    pub created_at: u64,
+}
+
+// Launch being instantiated, kept between the instantiation replies.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingLaunch {
+    pub project_owner: CanonicalAddr,
+    pub label: String,
+    pub presale: PresaleInstantiateMsg,
+    // Set once the vesting contract is instantiated.
+    pub vesting: Option<CanonicalAddr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Launches by id, in launch order.
+pub const LAUNCHES: Map<u64, Launch> = Map::new("launches");
+
+pub const LAUNCH_COUNT: Item<u64> = Item::new("launch_count");
+
+pub const PENDING_LAUNCH: Item<PendingLaunch> = Item::new("pending_launch");
+
+pub const INSTANTIATE_VESTING_REPLY_ID: u64 = 1u64;
+
+pub const INSTANTIATE_PRESALE_REPLY_ID: u64 = 2u64;
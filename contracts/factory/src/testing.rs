@@ -0,0 +1,324 @@
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, LaunchResponse, LaunchesResponse, MigrateMsg, QueryMsg};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, to_binary, ContractResult, CosmosMsg, Decimal, Event, OwnedDeps, Reply, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw2::{get_contract_version, set_contract_version};
+use pagination::MAX_LIMIT;
+use versioning::MigrationError;
+use presale::msg::{AcceptedAssetInfo, GetSaleStatusResponse, InstantiateMsg as PresaleInstantiateMsg, QueryMsg as PresaleQueryMsg, RoundInfo};
+use presale::state::{AllocationType, SaleMode, SaleStatus};
+use presale::types::FundAsset;
+use vesting::msg::InstantiateMsg as VestingInstantiateMsg;
+
+fn mock_vesting_msg() -> VestingInstantiateMsg {
+    VestingInstantiateMsg {
+        reward_token: "reward_token".to_string(),
+        release_interval: 60,
+        release_rate: 10,
+        initial_unlock: 10,
+        lock_period: 600,
+        vesting_period: 6000,
+        distribution_amount: 1000000,
+    }
+}
+
+fn mock_presale_msg() -> PresaleInstantiateMsg {
+    PresaleInstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(1u128) }],
+        reward_token: "reward_token".to_string(),
+        vesting: None,
+        claim_start_time: None,
+        rounds: vec![RoundInfo {
+            name: "round".to_string(),
+            start_time: 0,
+            end_time: 100,
+            price_multiplier: Decimal::one(),
+            whitelist_merkle_root: "root".to_string(),
+            allocation_type: AllocationType::Guaranteed,
+            max_allocation: None,
+            cap: None,
+        }],
+
+        total_rewards_amount: Uint128::from(1000000u128),
+        soft_cap: Uint128::zero(),
+        tranches: vec![],
+        sale_mode: SaleMode::FixedPrice,
+        treasury: "treasury".to_string(),
+        platform_fee_bps: 0,
+        liquidity: None,
+        staking_tiers: None,
+        lock_requirement: None,
+        lottery: None,
+        cool_off: None,
+        whitelist_leaf_format: None,
+        whitelists: vec![],
+        kyc_signer: None
+    }
+}
+
+fn mock_instantiate_reply(id: u64, contract_addr: &str) -> Reply {
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("instantiate").add_attribute("_contract_address", contract_addr)],
+            data: None,
+        }),
+    }
+}
+
+// Launches a project through both instantiation replies.
+fn launch(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, label: &str, vesting: &str, presale: &str) {
+    let info = mock_info("owner", &[]);
+    let msg = ExecuteMsg::Launch {
+        project_owner: "project".to_string(),
+        label: label.to_string(),
+        vesting: mock_vesting_msg(),
+        presale: Box::new(mock_presale_msg()),
+    };
+    let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let _ = reply(deps.as_mut(), mock_env(), mock_instantiate_reply(1, vesting)).unwrap();
+    let _ = reply(deps.as_mut(), mock_env(), mock_instantiate_reply(2, presale)).unwrap();
+}
+
+// Presale statuses by address, the others are pending.
+fn mock_sale_statuses(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, statuses: &[(&str, SaleStatus)]) {
+    let statuses: Vec<(String, SaleStatus)> = statuses.iter().map(|(presale, status)| (presale.to_string(), status.clone())).collect();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } => {
+            assert_eq!(from_binary::<PresaleQueryMsg>(msg).unwrap(), PresaleQueryMsg::GetSaleStatus {});
+            let status = statuses.iter().find(|(presale, _)| presale == contract_addr).map_or(SaleStatus::Pending, |(_, status)| status.clone());
+            SystemResult::Ok(ContractResult::Ok(to_binary(&GetSaleStatusResponse {
+                sold_amount: Uint128::zero(),
+                raised_amounts: vec![],
+                status,
+                clearing_price: None,
+            }).unwrap()))
+        }
+        _ => panic!("unexpected query"),
+    });
+}
+
+#[test]
+fn test_initialize() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config, ConfigResponse {
+        owner: "owner".to_string(),
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    });
+}
+
+#[test]
+fn test_update_config() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("new_owner".to_string()),
+        presale_code_id: Some(4),
+        vesting_code_id: None,
+    };
+    let info = mock_info("user", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let info = mock_info("owner", &[]);
+    let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config, ConfigResponse {
+        owner: "new_owner".to_string(),
+        presale_code_id: 4,
+        vesting_code_id: 1,
+    });
+}
+
+#[test]
+fn test_launch() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let msg = ExecuteMsg::Launch {
+        project_owner: "project".to_string(),
+        label: "project".to_string(),
+        vesting: mock_vesting_msg(),
+        presale: Box::new(mock_presale_msg()),
+    };
+
+    // only the owner launches projects
+    let info = mock_info("user", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    // launched presales vest through the launched vesting
+    let info = mock_info("owner", &[]);
+    let mut presale = mock_presale_msg();
+    presale.claim_start_time = Some(100);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Launch {
+        project_owner: "project".to_string(),
+        label: "project".to_string(),
+        vesting: mock_vesting_msg(),
+        presale: Box::new(presale),
+    });
+    assert_eq!(res.unwrap_err(), ContractError::InvalidInput {});
+
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::reply_on_success(WasmMsg::Instantiate {
+        admin: Some("owner".to_string()),
+        code_id: 1,
+        msg: to_binary(&mock_vesting_msg()).unwrap(),
+        funds: vec![],
+        label: "project vesting".to_string(),
+    }, 1)]);
+
+    // one launch at a time
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res.unwrap_err(), ContractError::LaunchInProgress {});
+
+    // the presale is instantiated with the vesting contract
+    let res = reply(deps.as_mut(), mock_env(), mock_instantiate_reply(1, "vesting0000")).unwrap();
+    let mut presale = mock_presale_msg();
+    presale.vesting = Some("vesting0000".to_string());
+    assert_eq!(res.messages, vec![SubMsg::reply_on_success(WasmMsg::Instantiate {
+        admin: Some("owner".to_string()),
+        code_id: 2,
+        msg: to_binary(&presale).unwrap(),
+        funds: vec![],
+        label: "project presale".to_string(),
+    }, 2)]);
+
+    // the presale becomes the vesting worker and the project owns both contracts
+    let res = reply(deps.as_mut(), mock_env(), mock_instantiate_reply(2, "presale0000")).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting0000".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::SetWorker { worker: "presale0000".to_string() }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting0000".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::TransferOwnerShip { new_owner: "project".to_string() }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "presale0000".to_string(),
+            msg: to_binary(&presale::msg::ExecuteMsg::TransferOwnerShip { new_owner: "project".to_string() }).unwrap(),
+            funds: vec![],
+        })),
+    ]);
+
+    mock_sale_statuses(&mut deps, &[]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Launch { launch_id: 0 }).unwrap();
+    let launch: LaunchResponse = from_binary(&res).unwrap();
+    assert_eq!(launch, LaunchResponse {
+        launch_id: 0,
+        project_owner: "project".to_string(),
+        presale: "presale0000".to_string(),
+        vesting: "vesting0000".to_string(),
+        status: SaleStatus::Pending,
+        created_at: mock_env().block.time.seconds(),
+    });
+
+    // the next launch can start
+    let info = mock_info("owner", &[]);
+    let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // replies are only expected for the launch instantiations
+    let res = reply(deps.as_mut(), mock_env(), mock_instantiate_reply(3, "vesting0001"));
+    assert_eq!(res.unwrap_err(), ContractError::InvalidReply {});
+    let res = reply(deps.as_mut(), mock_env(), Reply { id: 1, result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }) });
+    assert_eq!(res.unwrap_err(), ContractError::InvalidReply {});
+}
+
+#[test]
+fn test_launches() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    launch(&mut deps, "first", "vesting0000", "presale0000");
+    launch(&mut deps, "second", "vesting0001", "presale0001");
+    launch(&mut deps, "third", "vesting0002", "presale0002");
+
+    // the status is read from the presales, the second one succeeded
+    mock_sale_statuses(&mut deps, &[("presale0001", SaleStatus::Succeeded)]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Launches { status: Some(SaleStatus::Succeeded), start_after: None, limit: None }).unwrap();
+    let launches: LaunchesResponse = from_binary(&res).unwrap();
+    assert_eq!(launches.launches.iter().map(|launch| launch.launch_id).collect::<Vec<u64>>(), vec![1]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Launches { status: Some(SaleStatus::Pending), start_after: None, limit: None }).unwrap();
+    let launches: LaunchesResponse = from_binary(&res).unwrap();
+    assert_eq!(launches.launches.iter().map(|launch| launch.launch_id).collect::<Vec<u64>>(), vec![0, 2]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Launches { status: None, start_after: Some(0), limit: Some(1) }).unwrap();
+    let launches: LaunchesResponse = from_binary(&res).unwrap();
+    assert_eq!(launches.launches.iter().map(|launch| launch.launch_id).collect::<Vec<u64>>(), vec![1]);
+    assert_eq!(launches.last_launch_id, Some(1));
+
+    // a filtered page examines a bounded window of launches
+    for i in 0..MAX_LIMIT {
+        launch(&mut deps, "pending", &format!("vesting{:04}", i + 3), &format!("presale{:04}", i + 3));
+    }
+    let cancelled = format!("presale{:04}", MAX_LIMIT + 3);
+    launch(&mut deps, "cancelled", "vesting", &cancelled);
+    mock_sale_statuses(&mut deps, &[(&cancelled, SaleStatus::Cancelled)]);
+
+    let msg = QueryMsg::Launches { status: Some(SaleStatus::Cancelled), start_after: Some(2), limit: None };
+    let launches: LaunchesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert!(launches.launches.is_empty());
+    assert_eq!(launches.last_launch_id, Some(2 + MAX_LIMIT as u64));
+    let msg = QueryMsg::Launches { status: Some(SaleStatus::Cancelled), start_after: launches.last_launch_id, limit: None };
+    let launches: LaunchesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(launches.launches.iter().map(|launch| launch.launch_id).collect::<Vec<u64>>(), vec![3 + MAX_LIMIT as u64]);
+}
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        presale_code_id: 2,
+        vesting_code_id: 1,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let _ = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(get_contract_version(&deps.storage).unwrap().contract, "crates.io:factory");
+
+    set_contract_version(&mut deps.storage, "crates.io:presale", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::InvalidContract {}));
+
+    set_contract_version(&mut deps.storage, "crates.io:factory", "9.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::CannotDowngrade {}));
+}
@@ -13,7 +13,13 @@ use crate::msg::{
     ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, LockInfoResponse, LockedAccountsResponse, Cw20HookMsg, MigrateMsg,
 };
 use crate::types::OrderBy;
+use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use versioning::{assert_migration, migration_attributes};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:locking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -31,13 +37,19 @@ pub fn instantiate(
             dead: deps.api.addr_canonicalize(&msg.dead)?
         },
     )?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION)))
 }
 
 
@@ -1,6 +1,6 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg,
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, Cw20HookMsg,
 };
 
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
@@ -8,11 +8,12 @@ use cosmwasm_std::{
     attr, from_binary, to_binary, CosmosMsg, StdError, SubMsg, Timestamp,
     Uint128, WasmMsg,
 };
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 #[test]
 fn proper_initialization() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
 
     let msg = InstantiateMsg {
         owner: "owner".to_string(),
@@ -40,7 +41,7 @@ fn proper_initialization() {
 
 #[test]
 fn update_config() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
 
     let msg = InstantiateMsg {
         owner: "owner".to_string(),
@@ -112,7 +113,7 @@ fn update_config() {
 
 #[test]
 fn deposit_and_withdraw() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
 
     let msg = InstantiateMsg {
         owner: "owner".to_string(),
@@ -180,3 +181,42 @@ fn deposit_and_withdraw() {
         }))],
     );
 }
+
+#[test]
+fn migration() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        token: "token".to_string(),
+        penalty_period: 12345u64,
+        dead: "dead".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:locking");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("previous_version", env!("CARGO_PKG_VERSION")),
+            attr("new_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+
+    set_contract_version(&mut deps.storage, "crates.io:locking", "99.0.0").unwrap();
+    match migrate(deps.as_mut(), mock_env(), MigrateMsg {}) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate to an older version"),
+        _ => panic!("Must return generic error"),
+    }
+
+    set_contract_version(&mut deps.storage, "crates.io:staking", "0.1.0").unwrap();
+    match migrate(deps.as_mut(), mock_env(), MigrateMsg {}) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate from a different contract"),
+        _ => panic!("Must return generic error"),
+    }
+}
@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use presale::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ParticipantsCountResponse, GetSaleStatusResponse, GetParticipantResponse, GetParticipantsResponse};
+use presale::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, ParticipantsCountResponse, GetSaleStatusResponse, GetParticipantResponse, GetParticipantsResponse, PresaleInfoResponse, RoundsResponse, CurrentTrancheResponse, OversubscriptionResponse, LotteryResponse, LotteryWinnersResponse, VerifyLotteryResponse, WhitelistResponse, WhitelistsResponse, TicketNonceResponse};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -13,9 +13,20 @@ fn main() {
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(ParticipantsCountResponse), &out_dir);
     export_schema(&schema_for!(GetSaleStatusResponse), &out_dir);
     export_schema(&schema_for!(GetParticipantResponse), &out_dir);
     export_schema(&schema_for!(GetParticipantsResponse), &out_dir);
+    export_schema(&schema_for!(PresaleInfoResponse), &out_dir);
+    export_schema(&schema_for!(RoundsResponse), &out_dir);
+    export_schema(&schema_for!(CurrentTrancheResponse), &out_dir);
+    export_schema(&schema_for!(OversubscriptionResponse), &out_dir);
+    export_schema(&schema_for!(LotteryResponse), &out_dir);
+    export_schema(&schema_for!(LotteryWinnersResponse), &out_dir);
+    export_schema(&schema_for!(VerifyLotteryResponse), &out_dir);
+    export_schema(&schema_for!(WhitelistResponse), &out_dir);
+    export_schema(&schema_for!(WhitelistsResponse), &out_dir);
+    export_schema(&schema_for!(TicketNonceResponse), &out_dir);
 }
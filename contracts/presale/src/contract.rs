@@ -2,14 +2,25 @@ use std::convert::TryInto;
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, CosmosMsg, WasmMsg, Uint128, WasmQuery, QueryRequest, attr, BankMsg, Coin};
-use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, BalanceResponse };
+use cosmwasm_std::{from_binary, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, Storage, CosmosMsg, SubMsg, WasmMsg, Uint128, Uint256, attr};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use versioning::{assert_migration, migration_attributes, Version};
 use sha2::Digest;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ParticipantsCountResponse, GetParticipantResponse, GetParticipantsResponse, GetSaleStatusResponse, MigrateMsg, PresaleInfoResponse};
-use crate::querier::{query_decimals, query_balance};
-use crate::state::{PARTICIPANTS, PRIVATE_SOLD_FUNDS, ACCURACY, State, Participant, AlloInfo, store_state, read_state};
+use crate::msg::{AllocationTicket, CurrentTrancheResponse, Cw20HookMsg, ExecuteMsg, FundDecimals, InstantiateMsg, LiquidityInfo, LockRequirementInfo, LotteryResponse, LotteryWinnerInfo, LotteryWinnersResponse, StakingTiersInfo, VerifyLotteryResponse, TicketNonceResponse, WhitelistInfo, WhitelistResponse, WhitelistsResponse, QueryMsg, OversubscriptionResponse, RoundsResponse, ParticipantsCountResponse, GetParticipantResponse, GetParticipantsResponse, GetSaleStatusResponse, MigrateMsg, ParticipantInfo, PresaleInfoResponse};
+use crate::lottery::{draw_winners, lottery_commitment, lottery_seed, registrations_hash};
+use crate::merkle::{whitelist_leaf, LeafContext};
+use crate::ticket::{ticket_message, verify_ticket};
+use crate::pair::{Asset as PairAsset, AssetInfo, ExecuteMsg as PairExecuteMsg};
+use crate::querier::{query_bond_amount, query_decimals, query_fund_balance, query_lock_info, query_fund_decimals, query_pair_info, query_pool, query_token_balance};
+use crate::state::{LEGACY_PARTICIPANTS, LEGACY_PRIVATE_SOLD_FUNDS, LOTTERY_WINNERS, PARTICIPANTS, PARTICIPANTS_COUNT, REGISTRANTS, REGISTRATIONS, USED_TICKET_NONCES, WHITELISTS, ROUND_CONTRIBUTIONS, ROUND_SOLD_FUNDS, ACCURACY, FEE_DENOMINATOR, FUND_VALUE_DECIMALS, LIQUIDITY_SLIPPAGE_BPS, MAX_DECIMALS, PROVIDE_LIQUIDITY_REPLY_ID, AcceptedAsset, AllocationType, KycKeyType, KycSigner, LeafFormat, LiquidityConfig, LockRequirement, Lottery, LotteryDraw, Round, RoundContribution, State, Participant, TicketRedemption, SaleMode, SaleStatus, StakingTiers, TierAllocation, Whitelist, store_state, read_state, read_legacy_state, read_lottery_winners, read_participants, read_whitelists};
+use crate::types::{add_asset_amount, asset_amount, sub_asset_amount, Asset, FundAsset, OrderBy};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:presale";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -18,34 +29,422 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    if msg.soft_cap > msg.total_rewards_amount || msg.platform_fee_bps > FEE_DENOMINATOR {
+        return Err(ContractError::InvalidInput {});
+    }
+    if msg.accepted_assets.is_empty() || msg.rounds.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+    // the claim start time is only used without vesting, and is required then
+    if msg.vesting.is_some() == msg.claim_start_time.is_some() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    if msg.rounds.iter().any(|round| round.price_multiplier.is_zero()) {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    if !msg.tranches.is_empty() && (
+        msg.tranches.iter().map(|tranche| tranche.amount).sum::<Uint128>() != msg.total_rewards_amount ||
+        msg.tranches.iter().any(|tranche| tranche.amount.is_zero() || tranche.price_multiplier.is_zero())
+    ) {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    if let SaleMode::DutchAuction { start_price_multiplier, floor_price_multiplier } = msg.sale_mode {
+        if floor_price_multiplier.is_zero() || start_price_multiplier <= floor_price_multiplier || !msg.tranches.is_empty() ||
+            msg.rounds.iter().any(|round| round.price_multiplier != Decimal::one()) {
+            return Err(ContractError::InvalidInput {});
+        }
+    }
+    if msg.sale_mode == SaleMode::ProRata && !msg.tranches.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let mut accepted_assets: Vec<AcceptedAsset> = vec![];
+    for asset in msg.accepted_assets {
+        if let FundAsset::Cw20 { contract_addr } = &asset.info {
+            deps.api.addr_validate(contract_addr)?;
+        }
+        if asset.exchange_rate.is_zero() || accepted_assets.iter().any(|a| a.info == asset.info) {
+            return Err(ContractError::InvalidInput {});
+        }
+
+        accepted_assets.push(AcceptedAsset {
+            decimals: checked_decimals(query_fund_decimals(deps.as_ref(), &asset.info)?)?,
+            info: asset.info,
+            exchange_rate: asset.exchange_rate,
+        });
+    }
+
+    let rounds: Vec<Round> = msg.rounds.into_iter().map(|round| Round {
+        name: round.name,
+        start_time: round.start_time,
+        end_time: round.end_time,
+        price_multiplier: round.price_multiplier,
+        whitelist_merkle_root: round.whitelist_merkle_root,
+        allocation_type: round.allocation_type,
+        max_allocation: round.max_allocation,
+        cap: round.cap,
+        sold_amount: Uint128::zero(),
+    }).collect();
+    validate_rounds(&rounds)?;
+
+    let liquidity = match msg.liquidity {
+        Some(liquidity) => {
+            if liquidity.liquidity_bps > FEE_DENOMINATOR || !accepted_assets.iter().any(|asset| asset.info == liquidity.fund_asset) {
+                return Err(ContractError::InvalidInput {});
+            }
+
+            // the pair has to trade the fund asset against the reward token
+            let pair_info = query_pair_info(deps.as_ref(), deps.api.addr_validate(&liquidity.pair)?.to_string())?;
+            let reward_info = AssetInfo::Token { contract_addr: msg.reward_token.clone() };
+            if !pair_info.asset_infos.contains(&AssetInfo::from(&liquidity.fund_asset)) || !pair_info.asset_infos.contains(&reward_info) {
+                return Err(ContractError::InvalidInput {});
+            }
+
+            Some(LiquidityConfig {
+                pair: deps.api.addr_canonicalize(&liquidity.pair)?,
+                liquidity_token: deps.api.addr_canonicalize(&pair_info.liquidity_token)?,
+                fund_asset: liquidity.fund_asset,
+                liquidity_bps: liquidity.liquidity_bps,
+                locking: liquidity.locking.map(|locking| deps.api.addr_canonicalize(&locking)).transpose()?,
+            })
+        }
+        None => None,
+    };
+
+    let staking_tiers = match msg.staking_tiers {
+        Some(staking_tiers) => {
+            if staking_tiers.tiers.is_empty() ||
+                staking_tiers.tiers.windows(2).any(|tiers| tiers[0].min_bond_amount >= tiers[1].min_bond_amount) {
+                return Err(ContractError::InvalidInput {});
+            }
+
+            // weighted tiers scale the max allocation of the round
+            for tier in staking_tiers.tiers.iter() {
+                let valid = match tier.allocation {
+                    TierAllocation::Amount(amount) => !amount.is_zero(),
+                    TierAllocation::Weight(weight) => !weight.is_zero() && rounds.iter()
+                        .filter(|round| round.allocation_type == AllocationType::Staking)
+                        .all(|round| round.max_allocation.is_some()),
+                };
+                if !valid {
+                    return Err(ContractError::InvalidInput {});
+                }
+            }
+
+            Some(StakingTiers {
+                staking: deps.api.addr_canonicalize(&staking_tiers.staking)?,
+                tiers: staking_tiers.tiers,
+            })
+        }
+        None => None,
+    };
+    if staking_tiers.is_none() && rounds.iter().any(|round| round.allocation_type == AllocationType::Staking) {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let lock_requirement = match msg.lock_requirement {
+        Some(lock_requirement) => {
+            if lock_requirement.min_amount.is_zero() {
+                return Err(ContractError::InvalidInput {});
+            }
+
+            Some(LockRequirement {
+                locking: deps.api.addr_canonicalize(&lock_requirement.locking)?,
+                min_amount: lock_requirement.min_amount,
+                snapshot_time: lock_requirement.snapshot_time,
+            })
+        }
+        None => None,
+    };
+
+    let lottery = match msg.lottery {
+        Some(lottery) => {
+            if lottery.winners_count == 0 || lottery.allocation.is_zero() || lottery.reveal_deadline <= lottery.registration_end_time {
+                return Err(ContractError::InvalidInput {});
+            }
+
+            Some(Lottery {
+                registration_end_time: lottery.registration_end_time,
+                reveal_deadline: lottery.reveal_deadline,
+                winners_count: lottery.winners_count,
+                allocation: lottery.allocation,
+                registrants_count: 0,
+                registrations_hash: String::new(),
+                commitment: parse_commitment(&lottery.commitment)?,
+                draw: None,
+            })
+        }
+        None => None,
+    };
+    validate_lottery_rounds(&rounds, lottery.as_ref())?;
+
+    if let Some(kyc_signer) = &msg.kyc_signer {
+        validate_kyc_signer(kyc_signer)?;
+    }
+
+    if let Some(cool_off) = &msg.cool_off {
+        if cool_off.period == 0 || cool_off.fee_bps > FEE_DENOMINATOR {
+            return Err(ContractError::InvalidInput {});
+        }
+    }
+
     let state = State {
         owner: deps.api.addr_canonicalize(info.sender.as_str())?,
-        fund_denom: msg.fund_denom,
+        accepted_assets,
         reward_token: deps.api.addr_canonicalize(msg.reward_token.as_str())?,
-        vesting: deps.api.addr_canonicalize(msg.vesting.as_str())?,
-        whitelist_merkle_root: msg.whitelist_merkle_root,
+        reward_decimals: checked_decimals(query_decimals(deps.as_ref(), msg.reward_token.clone())?)?,
+        vesting: msg.vesting.map(|vesting| deps.api.addr_canonicalize(&vesting)).transpose()?,
+        treasury: deps.api.addr_canonicalize(msg.treasury.as_str())?,
 
-        exchange_rate: msg.exchange_rate,
-        presale_period: msg.presale_period,
-        public_start_time: msg.public_start_time,
-        private_start_time: msg.private_start_time,
+        rounds,
         total_rewards_amount: msg.total_rewards_amount,
-
-        private_sold_amount: Uint128::zero(),
-        public_sold_amount: Uint128::zero(),
-        userlist: vec![],
+        soft_cap: msg.soft_cap,
+        tranches: msg.tranches,
+        sale_mode: msg.sale_mode,
+        platform_fee_bps: msg.platform_fee_bps,
+        claim_start_time: msg.claim_start_time,
+        liquidity,
+        staking_tiers,
+        lock_requirement,
+        lottery,
+        cool_off: msg.cool_off,
+        whitelist_leaf_format: msg.whitelist_leaf_format.unwrap_or(LeafFormat::V1),
+        kyc_signer: msg.kyc_signer,
+
+        raised_amounts: vec![],
+        status: SaleStatus::Pending,
+        bought_amounts: vec![],
+        clearing_price: None,
+        funds_withdrawn: false,
+        provided_liquidity: vec![],
+        claimed_amount: Uint128::zero(),
     };
 
     store_state(deps.storage, &state)?;
+    for whitelist in msg.whitelists {
+        let (id, whitelist) = validate_whitelist(&state.rounds, whitelist)?;
+        if WHITELISTS.has(deps.storage, id.clone()) {
+            return Err(ContractError::InvalidInput {});
+        }
+        WHITELISTS.save(deps.storage, id, &whitelist)?;
+    }
+    PARTICIPANTS_COUNT.save(deps.storage, &0u64)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new())
 }
 
+/// Rounds have to be in order and must not overlap, so that each round is closed before the next one opens.
+fn validate_rounds(rounds: &[Round]) -> Result<(), ContractError> {
+    if rounds.iter().any(|round| round.start_time >= round.end_time) ||
+        rounds.windows(2).any(|pair| pair[0].end_time >= pair[1].start_time) {
+        return Err(ContractError::InvalidInput {});
+    }
+    Ok(())
+}
+
+/// Lottery rounds need the lottery and start once its reveal deadline has passed.
+fn validate_lottery_rounds(rounds: &[Round], lottery: Option<&Lottery>) -> Result<(), ContractError> {
+    for round in rounds.iter().filter(|round| round.allocation_type == AllocationType::Lottery) {
+        match lottery {
+            Some(lottery) if round.start_time > lottery.reveal_deadline => {}
+            _ => return Err(ContractError::InvalidInput {}),
+        }
+    }
+    Ok(())
+}
+
+/// Whitelists are open to guaranteed and fcfs rounds only and need a hex-encoded 32-byte merkle root.
+fn validate_whitelist(rounds: &[Round], whitelist: WhitelistInfo) -> Result<(String, Whitelist), ContractError> {
+    let mut root_buf: [u8; 32] = [0; 32];
+    if whitelist.id.is_empty() || whitelist.round_ids.is_empty() || hex::decode_to_slice(&whitelist.merkle_root, &mut root_buf).is_err() {
+        return Err(ContractError::InvalidInput {});
+    }
+    for round_id in whitelist.round_ids.iter() {
+        match rounds.get(*round_id as usize) {
+            Some(round) if matches!(round.allocation_type, AllocationType::Guaranteed | AllocationType::Fcfs) => {}
+            _ => return Err(ContractError::InvalidInput {}),
+        }
+    }
+
+    Ok((whitelist.id, Whitelist {
+        merkle_root: whitelist.merkle_root,
+        round_ids: whitelist.round_ids,
+        cap: whitelist.cap,
+        sold_amount: Uint128::zero(),
+    }))
+}
+
+fn validate_kyc_signer(signer: &KycSigner) -> Result<(), ContractError> {
+    let valid = match signer.key_type {
+        KycKeyType::Secp256k1 => matches!(signer.public_key.len(), 33 | 65),
+        KycKeyType::Ed25519 => signer.public_key.len() == 32,
+    };
+    if !valid {
+        return Err(ContractError::InvalidInput {});
+    }
+    Ok(())
+}
+
+/// Prices are computed with 10^decimals, whether the decimals come from the owner or a token.
+fn checked_decimals(decimals: u32) -> Result<u32, ContractError> {
+    if decimals > MAX_DECIMALS {
+        return Err(ContractError::InvalidInput {});
+    }
+    Ok(decimals)
+}
+
+/// Lottery commitments are hex-encoded sha256 hashes, stored in lower case.
+fn parse_commitment(commitment: &str) -> Result<String, ContractError> {
+    let mut commitment_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(commitment, &mut commitment_buf)?;
+    Ok(hex::encode(commitment_buf))
+}
+
 /************************************ Migration *************************************/
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::new())
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut attrs = vec![];
+
+    /* 0.2.0: Convert the private and public presale into a single round */
+    if !matches!(previous_version, Some(ref version) if *version >= Version::new(0, 2, 0)) {
+        let participants_count = migrate_legacy_state(deps.branch(), &env)?;
+        attrs.push(attr("migrated_participants", participants_count.to_string()));
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION))
+        .add_attributes(attrs))
+}
+
+/// Converts the state and the participants of a presale deployed before versioning.
+/// Its whitelist leaves hashed both phase allocations, which deposits no longer carry,
+/// so a whitelisted presale is only migrated once it stopped taking deposits and its root is dropped.
+fn migrate_legacy_state(deps: DepsMut, env: &Env) -> Result<u64, ContractError> {
+    let legacy_state = read_legacy_state(deps.storage)?;
+    let end_time = legacy_state.public_start_time + legacy_state.presale_period;
+    if !legacy_state.whitelist_merkle_root.is_empty() && env.block.time.seconds() <= end_time {
+        return Err(ContractError::LegacyWhitelist {});
+    }
+    let fund_asset = FundAsset::Native { denom: legacy_state.fund_denom.clone() };
+
+    let mut raised_amount = Uint128::zero();
+    for user in legacy_state.userlist.iter() {
+        let legacy_participant = LEGACY_PARTICIPANTS.load(deps.storage, user.clone())?;
+
+        // the private phase funds are part of the fund balance, all of it counts against the allocation of the round
+        LEGACY_PRIVATE_SOLD_FUNDS.remove(deps.storage, user.clone());
+        ROUND_SOLD_FUNDS.save(deps.storage, (0, user.clone()), &legacy_participant.fund_balance)?;
+        PARTICIPANTS.save(deps.storage, user.clone(), &Participant {
+            fund_balance: legacy_participant.fund_balance,
+            fund_balances: vec![Asset { info: fund_asset.clone(), amount: legacy_participant.fund_balance }],
+            reward_balance: legacy_participant.reward_balance,
+            bought_amounts: vec![Asset { info: fund_asset.clone(), amount: legacy_participant.reward_balance }],
+            settled: false,
+            claimed: false,
+        })?;
+        raised_amount += legacy_participant.fund_balance;
+    }
+    let participants_count = legacy_state.userlist.len() as u64;
+    PARTICIPANTS_COUNT.save(deps.storage, &participants_count)?;
+
+    let sold_amount = legacy_state.private_sold_amount + legacy_state.public_sold_amount;
+    let reward_token = deps.api.addr_humanize(&legacy_state.reward_token)?.to_string();
+    let state = State {
+        owner: legacy_state.owner.clone(),
+        // the fund denom was priced with 6 decimals
+        accepted_assets: vec![AcceptedAsset { info: fund_asset.clone(), exchange_rate: legacy_state.exchange_rate, decimals: 6 }],
+        reward_token: legacy_state.reward_token,
+        reward_decimals: checked_decimals(query_decimals(deps.as_ref(), reward_token)?)?,
+        vesting: Some(legacy_state.vesting),
+        treasury: legacy_state.owner,
+
+        rounds: vec![Round {
+            name: "presale".to_string(),
+            start_time: legacy_state.private_start_time.min(legacy_state.public_start_time),
+            end_time,
+            price_multiplier: Decimal::one(),
+            whitelist_merkle_root: "".to_string(),
+            allocation_type: AllocationType::Guaranteed,
+            max_allocation: None,
+            cap: None,
+            sold_amount,
+        }],
+        total_rewards_amount: legacy_state.total_rewards_amount,
+        soft_cap: Uint128::zero(),
+        tranches: vec![],
+        sale_mode: SaleMode::FixedPrice,
+        platform_fee_bps: 0,
+        claim_start_time: None,
+        liquidity: None,
+        staking_tiers: None,
+        lock_requirement: None,
+        lottery: None,
+        cool_off: None,
+        whitelist_leaf_format: LeafFormat::V1,
+        kyc_signer: None,
+
+        raised_amounts: vec![Asset { info: fund_asset.clone(), amount: raised_amount }],
+        status: SaleStatus::Pending,
+        bought_amounts: vec![Asset { info: fund_asset, amount: sold_amount }],
+        clearing_price: None,
+        funds_withdrawn: false,
+        provided_liquidity: vec![],
+        claimed_amount: Uint128::zero(),
+    };
+    store_state(deps.storage, &state)?;
+
+    Ok(participants_count)
+}
+
+
+/************************************ Reply *************************************/
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        PROVIDE_LIQUIDITY_REPLY_ID => reply_provide_liquidity(deps, env),
+        _ => Err(ContractError::InvalidInput {}),
+    }
+}
+
+/// Locks the LP tokens received from the pair, or sends them to the owner.
+fn reply_provide_liquidity(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+    let liquidity = state.liquidity.ok_or(ContractError::InvalidInput {})?;
+
+    let liquidity_token = deps.api.addr_humanize(&liquidity.liquidity_token)?;
+    let lp_amount = query_token_balance(deps.as_ref(), liquidity_token.clone(), env.contract.address)?;
+    let msg = match liquidity.locking {
+        Some(locking) => Cw20ExecuteMsg::Send {
+            contract: deps.api.addr_humanize(&locking)?.to_string(),
+            amount: lp_amount,
+            msg: to_binary(&locking::msg::Cw20HookMsg::Deposit {})?,
+        },
+        None => Cw20ExecuteMsg::Transfer {
+            recipient: deps.api.addr_humanize(&state.owner)?.to_string(),
+            amount: lp_amount,
+        },
+    };
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidity_token.to_string(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            attr("action", "provide_liquidity"),
+            attr("lp_amount", lp_amount),
+        ]))
 }
 
 /************************************ Execution *************************************/
@@ -58,25 +457,49 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+
         ExecuteMsg::TransferOwnerShip {
             new_owner
         } => execute_transfer_ownership(deps, info, new_owner),
 
-        ExecuteMsg::SetMerkleRoot { merkle_root } => execute_set_whitelist_merkle_root(deps, info, merkle_root),
+        ExecuteMsg::SetMerkleRoot { round_id, merkle_root } => execute_set_whitelist_merkle_root(deps, info, round_id, merkle_root),
+
+        ExecuteMsg::UpdateRound {
+            round_id,
+            new_start_time,
+            new_end_time
+        } => execute_update_round(deps, env, info, round_id, new_start_time, new_end_time),
 
-        ExecuteMsg::UpdatePresaleInfo {
-            new_private_start_time,
-            new_public_start_time,
-            new_presale_period
-        } => execute_update_info(deps, env, info, new_private_start_time, new_public_start_time, new_presale_period),
+        ExecuteMsg::UpdateDecimals { reward_decimals, fund_decimals } => execute_update_decimals(deps, env, info, reward_decimals, fund_decimals),
 
-        ExecuteMsg::Deposit { allo_info, proof } => execute_deposit(deps, env, info, allo_info, proof),
+        ExecuteMsg::Deposit { round_id, allocation, proof, whitelist_id, ticket } => execute_deposit(deps, env, info, round_id, WhitelistEntry { whitelist_id, allocation, proof, ticket }),
 
-        ExecuteMsg::DepositPrivateSale { allo_info, proof } => execute_deposit_private_sale(deps, env, info, allo_info, proof),
+        ExecuteMsg::SetKycSigner { signer } => execute_set_kyc_signer(deps, info, signer),
+
+        ExecuteMsg::AddWhitelist { whitelist } => execute_add_whitelist(deps, info, whitelist),
 
         ExecuteMsg::WithdrawFunds { receiver } => execute_withdraw_funds(deps, env, info, receiver),
 
         ExecuteMsg::WithdrawUnsoldToken { receiver } => execute_withdraw_unsold_token(deps, env, info, receiver),
+
+        ExecuteMsg::WithdrawContribution { round_id, asset, amount } => execute_withdraw_contribution(deps, env, info, round_id, asset, amount),
+
+        ExecuteMsg::Finalize {} => execute_finalize(deps, env),
+
+        ExecuteMsg::ClaimRefund {} => execute_claim_refund(deps, info),
+
+        ExecuteMsg::CancelSale {} => execute_cancel_sale(deps, env, info),
+
+        ExecuteMsg::ClearVesting { start_after, limit } => execute_clear_vesting(deps, info, start_after, limit),
+
+        ExecuteMsg::SettleBid {} => execute_settle_bid(deps, info),
+
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+
+        ExecuteMsg::Register {} => execute_register(deps, env, info),
+
+        ExecuteMsg::RevealLottery { secret } => execute_reveal_lottery(deps, env, info, secret),
     }
 }
 
@@ -98,7 +521,7 @@ pub fn execute_transfer_ownership(deps: DepsMut, info: MessageInfo, new_owner: S
     ]))
 }
 
-pub fn execute_set_whitelist_merkle_root(deps: DepsMut, info: MessageInfo, merkle_root: String) -> Result<Response, ContractError> {
+pub fn execute_set_whitelist_merkle_root(deps: DepsMut, info: MessageInfo, round_id: u64, merkle_root: String) -> Result<Response, ContractError> {
     let mut state: State = read_state(deps.storage)?;
 
     // permission check
@@ -106,16 +529,66 @@ pub fn execute_set_whitelist_merkle_root(deps: DepsMut, info: MessageInfo, merkl
         return Err(ContractError::Unauthorized {});
     }
 
-    state.whitelist_merkle_root = merkle_root.clone();
+    let round = state.rounds.get_mut(round_id as usize).ok_or(ContractError::RoundNotFound {})?;
+    round.whitelist_merkle_root = merkle_root.clone();
     store_state(deps.storage, &state)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "register_merkle_root"),
+        attr("round_id", round_id.to_string()),
         attr("merkle_root", merkle_root),
     ]))
 }
 
-pub fn execute_update_info(deps: DepsMut, env: Env, info: MessageInfo, new_private_start_time: u64, new_public_start_time: u64, new_presale_period: u64) -> Result<Response, ContractError> {
+pub fn execute_add_whitelist(deps: DepsMut, info: MessageInfo, whitelist: WhitelistInfo) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match state.status {
+        SaleStatus::Pending => {}
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        _ => return Err(ContractError::AlreadyFinalized {}),
+    }
+
+    let (id, whitelist) = validate_whitelist(&state.rounds, whitelist)?;
+    if WHITELISTS.has(deps.storage, id.clone()) {
+        return Err(ContractError::WhitelistExists {});
+    }
+    WHITELISTS.save(deps.storage, id.clone(), &whitelist)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_whitelist"),
+        attr("whitelist_id", id),
+        attr("merkle_root", whitelist.merkle_root),
+    ]))
+}
+
+pub fn execute_set_kyc_signer(deps: DepsMut, info: MessageInfo, signer: Option<KycSigner>) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(signer) = &signer {
+        validate_kyc_signer(signer)?;
+    }
+    let public_key = signer.as_ref().map(|signer| signer.public_key.to_base64()).unwrap_or_default();
+    state.kyc_signer = signer;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_kyc_signer"),
+        attr("public_key", public_key),
+    ]))
+}
+
+pub fn execute_update_round(deps: DepsMut, env: Env, info: MessageInfo, round_id: u64, new_start_time: u64, new_end_time: u64) -> Result<Response, ContractError> {
     let mut state: State = read_state(deps.storage)?;
 
     // permission check
@@ -123,43 +596,146 @@ pub fn execute_update_info(deps: DepsMut, env: Env, info: MessageInfo, new_priva
         return Err(ContractError::Unauthorized {});
     }
 
-    if new_private_start_time < env.block.time.seconds() ||  new_public_start_time < env.block.time.seconds() {
+    if new_start_time < env.block.time.seconds() {
         return Err(ContractError::InvalidInput {});
     }
 
-    state.private_start_time = new_private_start_time;
-    state.public_start_time = new_public_start_time;
-    state.presale_period = new_presale_period;
+    let round = state.rounds.get_mut(round_id as usize).ok_or(ContractError::RoundNotFound {})?;
+    round.start_time = new_start_time;
+    round.end_time = new_end_time;
+    validate_rounds(&state.rounds)?;
+    validate_lottery_rounds(&state.rounds, state.lottery.as_ref())?;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_round"),
+        attr("round_id", round_id.to_string()),
+        attr("new_start_time", new_start_time.to_string()),
+        attr("new_end_time", new_end_time.to_string()),
+    ]))
+}
+
+pub fn execute_update_decimals(deps: DepsMut, env: Env, info: MessageInfo, reward_decimals: Option<u32>, fund_decimals: Vec<FundDecimals>) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // prices are fixed once deposits are accepted
+    if env.block.time.seconds() >= state.start_time() {
+        return Err(ContractError::SaleStarted {});
+    }
+
+    if let Some(reward_decimals) = reward_decimals {
+        state.reward_decimals = checked_decimals(reward_decimals)?;
+    }
+    for fund_decimals in fund_decimals {
+        let asset = state.accepted_assets.iter_mut()
+            .find(|asset| asset.info == fund_decimals.info)
+            .ok_or(ContractError::InvalidInput {})?;
+        asset.decimals = checked_decimals(fund_decimals.decimals)?;
+    }
     store_state(deps.storage, &state)?;
 
     Ok(Response::new().add_attributes(vec![
-        attr("action", "set_start_time"),
-        attr("new_private_start_time", new_private_start_time.to_string()),
-        attr("new_public_start_time", new_public_start_time.to_string()),
-        attr("new_presale_period", new_presale_period.to_string()),
+        attr("action", "update_decimals"),
+        attr("reward_decimals", state.reward_decimals.to_string()),
     ]))
 }
 
-pub fn calc_reward_amount(deps: Deps, state: State, fund_amount: Uint128) -> StdResult<Uint128> {
-    let fund_decimals: u32 = 6;
-    let reward_decimals = query_decimals(deps, deps.api.addr_humanize(&state.reward_token)?.to_string())?;
+pub fn calc_reward_amount(state: &State, asset: &AcceptedAsset, price_multiplier: Decimal, fund_amount: Uint128) -> StdResult<Uint128> {
+    let fund_decimals = asset.decimals;
+    let exchange_rate = asset.exchange_rate * price_multiplier;
+    let reward_decimals = state.reward_decimals;
+
+    let numerator = Uint256::from(fund_amount)
+        .checked_mul(Uint256::from(ACCURACY))?
+        .checked_mul(Uint256::from(10u128.pow(reward_decimals)))?;
+    let denominator = Uint256::from(exchange_rate)
+        .checked_mul(Uint256::from(10u128.pow(fund_decimals)))?;
+
+    Ok(numerator.checked_div(denominator)?.try_into()?)
+}
+
+pub fn calc_fund_amount(state: &State, asset: &AcceptedAsset, price_multiplier: Decimal, reward_amount: Uint128) -> StdResult<Uint128> {
+    let fund_decimals = asset.decimals;
+    let exchange_rate = asset.exchange_rate * price_multiplier;
+    let reward_decimals = state.reward_decimals;
+
+    let numerator = Uint256::from(reward_amount)
+        .checked_mul(Uint256::from(exchange_rate))?
+        .checked_mul(Uint256::from(10u128.pow(fund_decimals)))?;
+    let denominator = Uint256::from(ACCURACY)
+        .checked_mul(Uint256::from(10u128.pow(reward_decimals)))?;
+
+    // round up so that the reward amount is always fully paid
+    Ok(numerator
+        .checked_add(denominator - Uint256::from(1u128))?
+        .checked_div(denominator)?
+        .try_into()?)
+}
+
+/// Vesting schedules hold u64 amounts, larger reward balances cannot be vested.
+pub fn vesting_amount(reward_balance: Uint128) -> Result<u64, ContractError> {
+    reward_balance.u128().try_into().map_err(|_| ContractError::ExceedVestingAmount {})
+}
 
-    Ok(fund_amount
-        .checked_mul(Uint128::from(ACCURACY)).unwrap()
-        .checked_div(state.exchange_rate).unwrap()
-        .checked_mul(Uint128::from(10u128).wrapping_pow(reward_decimals)).unwrap()
-        .checked_div(Uint128::from(10u128).wrapping_pow(fund_decimals)).unwrap()
-    )
+/// Normalizes a fund amount of the asset to FUND_VALUE_DECIMALS.
+pub fn calc_fund_value(asset: &AcceptedAsset, fund_amount: Uint128) -> Uint128 {
+    fund_amount.multiply_ratio(10u128.pow(FUND_VALUE_DECIMALS), 10u128.pow(asset.decimals))
 }
 
-pub fn verify_whitelist(state: State, sender: &String, allo_info: &AlloInfo, proof: &Vec<String>) -> Result<bool, ContractError> {
-    let user_input = format!("{}{}{}", sender, allo_info.private_allocation, allo_info.public_allocation);
-    let hash = sha2::Sha256::digest(user_input.as_bytes())
-        .as_slice()
-        .try_into()
-        .map_err(|_| ContractError::WrongLength {})?;
+/// Trims the fund amount to what can still be sold under the hard cap, the round cap and the whitelist cap.
+/// Pro-rata sales are not limited by the hard cap.
+/// The fund amount is priced piecewise over the tranches it crosses.
+/// Returns the accepted fund amount and the reward amount it buys.
+pub fn apply_hard_cap(state: &State, round: &Round, whitelist: Option<&Whitelist>, asset: &AcceptedAsset, price_multiplier: Decimal, fund_amount: Uint128) -> Result<(Uint128, Uint128), ContractError> {
+    let sold_amount = state.sold_amount();
+    let mut remaining_amount = match state.sale_mode {
+        SaleMode::ProRata => Uint128::MAX,
+        _ => state.total_rewards_amount.saturating_sub(sold_amount),
+    };
+    if let (Some(cap), AllocationType::Fcfs | AllocationType::Public) = (round.cap, &round.allocation_type) {
+        remaining_amount = remaining_amount.min(cap.saturating_sub(round.sold_amount));
+    }
+    if remaining_amount.is_zero() {
+        return Err(ContractError::HardCapReached {});
+    }
+    if let Some(Whitelist { cap: Some(cap), sold_amount: whitelist_sold_amount, .. }) = whitelist {
+        if whitelist_sold_amount >= cap {
+            return Err(ContractError::WhitelistCapReached {});
+        }
+        remaining_amount = remaining_amount.min(cap - whitelist_sold_amount);
+    }
 
-    let hash = proof.into_iter().try_fold(hash, |hash, p| {
+    let mut fund_left = fund_amount;
+    let mut reward_amount = Uint128::zero();
+    while !fund_left.is_zero() && reward_amount < remaining_amount {
+        let (tranche_multiplier, tranche_left) = match state.current_tranche(sold_amount + reward_amount) {
+            Some((index, tranche_left)) => (state.tranches[index].price_multiplier, tranche_left),
+            None => (Decimal::one(), Uint128::MAX),
+        };
+        let tranche_price = price_multiplier * tranche_multiplier;
+        let reward_limit = tranche_left.min(remaining_amount - reward_amount);
+
+        let tranche_reward = calc_reward_amount(state, asset, tranche_price, fund_left)?;
+        if tranche_reward <= reward_limit {
+            reward_amount += tranche_reward;
+            fund_left = Uint128::zero();
+        } else {
+            let tranche_fund = calc_fund_amount(state, asset, tranche_price, reward_limit)?.min(fund_left);
+            reward_amount += reward_limit;
+            fund_left -= tranche_fund;
+        }
+    }
+
+    Ok((fund_amount - fund_left, reward_amount))
+}
+
+pub fn verify_whitelist(merkle_root: &str, leaf: [u8; 32], proof: &[String]) -> Result<bool, ContractError> {
+    let hash = proof.iter().try_fold(leaf, |hash, p| {
         let mut proof_buf = [0; 32];
         hex::decode_to_slice(p, &mut proof_buf)?;
         let mut hashes = [hash, proof_buf];
@@ -171,160 +747,348 @@ pub fn verify_whitelist(state: State, sender: &String, allo_info: &AlloInfo, pro
     })?;
 
     let mut root_buf: [u8; 32] = [0; 32];
-    hex::decode_to_slice(state.whitelist_merkle_root.clone(), &mut root_buf)?;
+    hex::decode_to_slice(merkle_root, &mut root_buf)?;
     Ok(root_buf == hash)
 }
 
-pub fn execute_deposit(deps: DepsMut, env: Env, info: MessageInfo, allo_info: AlloInfo, proof: Vec<String>) -> Result<Response, ContractError> {
-    let mut state: State = read_state(deps.storage)?;
-    let sender = info.sender.to_string();
+/// Allocation of the staking tier reached by the amount the sender has bonded.
+pub fn staking_allocation(deps: Deps, state: &State, round: &Round, sender: &str) -> Result<Uint128, ContractError> {
+    let staking_tiers = state.staking_tiers.as_ref().ok_or(ContractError::InvalidInput {})?;
+    let bond_amount = query_bond_amount(deps, deps.api.addr_humanize(&staking_tiers.staking)?.to_string(), sender.to_string())?;
 
-    /* Check if Presale in progress */
-    let end_time = state.public_start_time + state.presale_period;
-    if env.block.time.seconds() > end_time || env.block.time.seconds() < state.public_start_time {
-        return Err(ContractError::PublicNotInProgress {});
-    }
+    // stakers below the lowest tier are not allowed in the round
+    let tier = staking_tiers.tier(bond_amount).ok_or(ContractError::NotWhitelisted {})?;
+    Ok(match tier.allocation {
+        TierAllocation::Amount(amount) => amount,
+        TierAllocation::Weight(weight) => round.max_allocation.unwrap_or_default() * weight,
+    })
+}
 
-    /* Check fund tokens */
-    let amount;
-    if let Some(coins) = info.funds.first() {
-        if coins.denom != state.fund_denom || coins.amount.is_zero() {
-            return Err(ContractError::Funds {  });
+/// Whitelist entry claimed by a deposit.
+pub struct WhitelistEntry {
+    pub whitelist_id: Option<String>,
+    pub allocation: Uint128,
+    pub proof: Vec<String>,
+    pub ticket: Option<AllocationTicket>,
+}
+
+pub fn execute_deposit(deps: DepsMut, env: Env, info: MessageInfo, round_id: u64, entry: WhitelistEntry) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+    let fund = native_fund(&state, &info)?;
+
+    deposit(deps, env, info.sender.to_string(), fund, round_id, entry)
+}
+
+pub fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::Deposit { round_id, allocation, proof, whitelist_id, ticket }) => {
+            // only accepted fund token contracts can execute this message
+            let fund_asset = FundAsset::Cw20 { contract_addr: info.sender.to_string() };
+            if !state.accepted_assets.iter().any(|asset| asset.info == fund_asset) {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let cw20_sender = deps.api.addr_validate(&cw20_msg.sender)?;
+            let fund = Asset { info: fund_asset, amount: cw20_msg.amount };
+            deposit(deps, env, cw20_sender.to_string(), fund, round_id, WhitelistEntry { whitelist_id, allocation, proof, ticket })
         }
-        amount = coins.amount;
-    } else {
-        return Err(ContractError::Funds {  });
+        Err(_) => Err(ContractError::InvalidInput {}),
     }
+}
 
-    /* Verify if whitelisted */
-    if state.whitelist_merkle_root.len() > 0 {
-        if verify_whitelist(state.clone(), &sender, &allo_info, &proof)? == false {
-            return Err(ContractError::NotWhitelisted {});
-        }
+fn native_fund(state: &State, info: &MessageInfo) -> Result<Asset, ContractError> {
+    if info.funds.len() != 1 {
+        return Err(ContractError::Funds {});
+    }
+
+    let fund_asset = FundAsset::Native { denom: info.funds[0].denom.clone() };
+    if !state.accepted_assets.iter().any(|asset| asset.info == fund_asset) {
+        return Err(ContractError::Funds {});
     }
 
+    Ok(Asset { info: fund_asset, amount: info.funds[0].amount })
+}
+
+fn deposit(deps: DepsMut, env: Env, sender: String, fund: Asset, round_id: u64, entry: WhitelistEntry) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+    let asset = state.accepted_assets.iter()
+        .find(|asset| asset.info == fund.info)
+        .cloned()
+        .ok_or(ContractError::Funds {})?;
+    let round = state.rounds.get(round_id as usize)
+        .cloned()
+        .ok_or(ContractError::RoundNotFound {})?;
+
+    /* Check if Round in progress */
+    if env.block.time.seconds() < round.start_time || env.block.time.seconds() > round.end_time {
+        return Err(ContractError::RoundNotInProgress {});
+    }
+    match state.status {
+        SaleStatus::Pending => {}
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        _ => return Err(ContractError::AlreadyFinalized {}),
+    }
 
+    /* Check fund tokens */
+    if fund.amount.is_zero() {
+        return Err(ContractError::Funds {});
+    }
 
-    let mut recp_info = Participant {
-        fund_balance: Uint128::zero(),
-        reward_balance: Uint128::zero()
+    /* Check locked tokens */
+    if let Some(lock_requirement) = &state.lock_requirement {
+        let lock_info = query_lock_info(deps.as_ref(), deps.api.addr_humanize(&lock_requirement.locking)?.to_string(), sender.clone())?;
+        if lock_info.amount < lock_requirement.min_amount || lock_info.last_locked_time > lock_requirement.snapshot_time {
+            return Err(ContractError::NotEligible {});
+        }
+    }
+
+    /* Verify if whitelisted */
+    if entry.ticket.is_some() && entry.whitelist_id.is_some() {
+        return Err(ContractError::InvalidInput {});
+    }
+    let whitelist = match &entry.whitelist_id {
+        Some(whitelist_id) => {
+            let whitelist = WHITELISTS.may_load(deps.storage, whitelist_id.clone())?.ok_or(ContractError::WhitelistNotFound {})?;
+            if !whitelist.round_ids.contains(&round_id) {
+                return Err(ContractError::NotWhitelisted {});
+            }
+            Some(whitelist)
+        }
+        None => None,
+    };
+    let allocation = match round.allocation_type {
+        AllocationType::Guaranteed | AllocationType::Fcfs => {
+            let ctx = LeafContext {
+                chain_id: &env.block.chain_id,
+                contract: env.contract.address.as_str(),
+                round_id,
+            };
+            match &entry.ticket {
+                Some(ticket) => {
+                    let kyc_signer = state.kyc_signer.as_ref().ok_or(ContractError::InvalidTicket {})?;
+                    if env.block.time.seconds() > ticket.expires_at {
+                        return Err(ContractError::TicketExpired {});
+                    }
+                    if let Some(redemption) = USED_TICKET_NONCES.may_load(deps.storage, ticket.nonce)? {
+                        if redemption.account != sender {
+                            return Err(ContractError::NonceUsed {});
+                        }
+                    }
+                    let message = ticket_message(&ctx, &sender, entry.allocation, ticket.expires_at, ticket.nonce);
+                    if !verify_ticket(deps.api, kyc_signer, &message, &ticket.signature) {
+                        return Err(ContractError::InvalidTicket {});
+                    }
+                }
+                None => {
+                    let merkle_root = whitelist.as_ref().map_or(&round.whitelist_merkle_root, |whitelist| &whitelist.merkle_root);
+                    if merkle_root.is_empty() {
+                        // without a merkle root, the tickets of the kyc signer are the only way in
+                        if state.kyc_signer.is_some() {
+                            return Err(ContractError::NotWhitelisted {});
+                        }
+                    } else {
+                        let leaf = whitelist_leaf(&state.whitelist_leaf_format, &ctx, &sender, entry.allocation);
+                        if !verify_whitelist(merkle_root, leaf, &entry.proof)? {
+                            return Err(ContractError::NotWhitelisted {});
+                        }
+                    }
+                }
+            }
+            entry.allocation
+        }
+        AllocationType::Public => round.max_allocation.unwrap_or(Uint128::MAX),
+        AllocationType::Staking => staking_allocation(deps.as_ref(), &state, &round, &sender)?,
+        AllocationType::Lottery => {
+            // lottery rounds start after the reveal deadline, there is no draw when the owner withheld the secret
+            if !matches!(&state.lottery, Some(lottery) if lottery.draw.is_some()) {
+                return Err(ContractError::LotteryCancelled {});
+            }
+            LOTTERY_WINNERS.may_load(deps.storage, sender.clone())?.ok_or(ContractError::NotWhitelisted {})?
+        }
     };
-    let mut private_sold_fund = Uint128::zero();
 
     /* Add to participants list */
-    if PARTICIPANTS.has(deps.storage, sender.clone()) {
-        recp_info = PARTICIPANTS.load(deps.storage, sender.clone())?;
-        private_sold_fund = PRIVATE_SOLD_FUNDS.load(deps.storage, sender.clone())?;
-    } else {
-        state.userlist.push(sender.clone());
-    }
+    let mut recp_info = match PARTICIPANTS.may_load(deps.storage, sender.clone())? {
+        Some(recp_info) => recp_info,
+        None => {
+            PARTICIPANTS_COUNT.update(deps.storage, |count| -> StdResult<u64> { Ok(count + 1) })?;
+            Participant::default()
+        }
+    };
+    let round_sold_fund = ROUND_SOLD_FUNDS.may_load(deps.storage, (round_id, sender.clone()))?.unwrap_or_default();
+
+    /* Check hard cap */
+    let auction_price = state.auction_price(env.block.time.seconds());
+    let price_multiplier = auction_price.unwrap_or(round.price_multiplier);
+    let (accepted_amount, reward_amount) = apply_hard_cap(&state, &round, whitelist.as_ref(), &asset, price_multiplier, fund.amount)?;
+    let refund_amount = fund.amount - accepted_amount;
+    let accepted_value = calc_fund_value(&asset, accepted_amount);
 
     /* Check allocation */
-    let new_fund_balance = recp_info.fund_balance + amount;
-    if allo_info.public_allocation + private_sold_fund < new_fund_balance {
-        return Err(ContractError::ExceedAllocation {  });
+    let new_round_sold_fund = round_sold_fund + accepted_value;
+    if allocation < new_round_sold_fund {
+        return Err(ContractError::ExceedAllocation {});
+    }
+
+    // tickets are redeemed by the accepted value, a deposit trimmed by the cap leaves the rest of the allocation
+    if let Some(ticket) = &entry.ticket {
+        let mut redemption = USED_TICKET_NONCES.may_load(deps.storage, ticket.nonce)?
+            .unwrap_or(TicketRedemption { account: sender.clone(), redeemed_value: Uint128::zero() });
+        redemption.redeemed_value += accepted_value;
+        if redemption.redeemed_value > entry.allocation {
+            return Err(ContractError::ExceedAllocation {});
+        }
+        USED_TICKET_NONCES.save(deps.storage, ticket.nonce, &redemption)?;
     }
 
     /* Update rewards amount */
-    let reward_amount = calc_reward_amount(deps.as_ref(), state.clone(), amount)?;
-    recp_info.fund_balance = new_fund_balance;
-    recp_info.reward_balance = recp_info.reward_balance + reward_amount;
-    state.public_sold_amount = state.public_sold_amount + reward_amount;
+    recp_info.fund_balance += accepted_value;
+    add_asset_amount(&mut recp_info.fund_balances, &fund.info, accepted_amount);
+    recp_info.reward_balance += reward_amount;
+    add_asset_amount(&mut recp_info.bought_amounts, &fund.info, reward_amount);
+    add_asset_amount(&mut state.raised_amounts, &fund.info, accepted_amount);
+    add_asset_amount(&mut state.bought_amounts, &fund.info, reward_amount);
+    state.rounds[round_id as usize].sold_amount += reward_amount;
+
+    /* Dutch auction clears at the price it sells out */
+    if auction_price.is_some() && state.sold_amount() >= state.total_rewards_amount {
+        state.clearing_price = auction_price;
+    }
 
     store_state(deps.storage, &state)?;
     PARTICIPANTS.save(deps.storage, sender.clone(), &recp_info)?;
+    ROUND_SOLD_FUNDS.save(deps.storage, (round_id, sender.clone()), &new_round_sold_fund)?;
+    if let (Some(whitelist_id), Some(mut whitelist)) = (entry.whitelist_id, whitelist) {
+        whitelist.sold_amount += reward_amount;
+        WHITELISTS.save(deps.storage, whitelist_id, &whitelist)?;
+    }
+    if state.cool_off.is_some() {
+        let mut contribution = ROUND_CONTRIBUTIONS.may_load(deps.storage, (round_id, sender.clone()))?
+            .unwrap_or(RoundContribution { first_deposit_time: env.block.time.seconds(), ..RoundContribution::default() });
+        add_asset_amount(&mut contribution.fund_amounts, &fund.info, accepted_amount);
+        add_asset_amount(&mut contribution.fund_values, &fund.info, accepted_value);
+        add_asset_amount(&mut contribution.bought_amounts, &fund.info, reward_amount);
+        ROUND_CONTRIBUTIONS.save(deps.storage, (round_id, sender.clone()), &contribution)?;
+    }
 
-    /* Update vesting */
+    /* Update vesting, dutch auction bids and pro-rata contributions are vested once settled */
     let mut messages: Vec<CosmosMsg> = vec![];
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps.api.addr_humanize(&state.vesting)?.to_string(),
-        msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
-            recp: sender.clone(),
-            amount: recp_info.reward_balance.u128().try_into().unwrap(),
-        })?,
-        funds: vec![],
-    }));
+    if let (SaleMode::FixedPrice, Some(vesting)) = (&state.sale_mode, &state.vesting) {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: sender.clone(),
+                amount: vesting_amount(recp_info.reward_balance)?,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    /* Refund the part over the hard cap */
+    if !refund_amount.is_zero() {
+        messages.push(fund.info.transfer_msg(sender, refund_amount)?);
+    }
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("method", "deposit"))
+        .add_attribute("method", "deposit")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("refund_amount", refund_amount))
 }
 
-pub fn execute_deposit_private_sale(deps: DepsMut, env: Env, info: MessageInfo, allo_info: AlloInfo, proof: Vec<String>) -> Result<Response, ContractError> {
+pub fn execute_withdraw_contribution(deps: DepsMut, env: Env, info: MessageInfo, round_id: u64, fund_asset: FundAsset, amount: Uint128) -> Result<Response, ContractError> {
     let mut state: State = read_state(deps.storage)?;
     let sender = info.sender.to_string();
-
-    /* Check if Presale in progress */
-    if env.block.time.seconds() < state.private_start_time {
-        return Err(ContractError::PrivateNotInProgress {  });
+    let cool_off = state.cool_off.clone().ok_or(ContractError::CoolOffNotAvailable {})?;
+    if !state.accepted_assets.iter().any(|asset| asset.info == fund_asset) {
+        return Err(ContractError::Funds {});
     }
-
-    /* Check fund tokens */
-    let amount ;
-    if let Some(coins) = info.funds.first() {
-        if coins.denom != state.fund_denom || coins.amount.is_zero() {
-            return Err(ContractError::Funds {  });
-        }
-        amount = coins.amount;
-    } else {
-        return Err(ContractError::Funds {  });
+    if round_id as usize >= state.rounds.len() {
+        return Err(ContractError::RoundNotFound {});
     }
-
-    /* Verify if whitelisted */
-    if state.whitelist_merkle_root.len() > 0 {
-        if verify_whitelist(state.clone(), &sender, &allo_info, &proof)? == false {
-            return Err(ContractError::NotWhitelisted {});
-        }
+    match state.status {
+        SaleStatus::Pending => {}
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        _ => return Err(ContractError::AlreadyFinalized {}),
     }
 
-
-    let mut recp_info = Participant {
-        fund_balance: Uint128::zero(),
-        reward_balance: Uint128::zero()
-    };
-    let mut private_sold_fund = Uint128::zero();
-
-    /* Add to participants list */
-    if PARTICIPANTS.has(deps.storage, sender.clone()) {
-        recp_info = PARTICIPANTS.load(deps.storage, sender.clone())?;
-        private_sold_fund = PRIVATE_SOLD_FUNDS.load(deps.storage, sender.clone())?;
-    } else {
-        state.userlist.push(sender.clone());
+    /* Check cool-off period */
+    let mut contribution = ROUND_CONTRIBUTIONS.may_load(deps.storage, (round_id, sender.clone()))?
+        .ok_or(ContractError::ExceedContribution {})?;
+    if env.block.time.seconds() > contribution.first_deposit_time + cool_off.period {
+        return Err(ContractError::CoolOffNotAvailable {});
     }
 
-    /* Check allocation */
-    let new_fund_balance = recp_info.fund_balance + amount;
-    if allo_info.private_allocation < new_fund_balance {
-        return Err(ContractError::ExceedAllocation {  });
+    /* Check contribution */
+    let fund_amount = asset_amount(&contribution.fund_amounts, &fund_asset);
+    if amount.is_zero() || amount > fund_amount {
+        return Err(ContractError::ExceedContribution {});
     }
 
+    // the withdrawn funds give back their share of the value and the tokens bought with the asset in the round
+    let fund_value = asset_amount(&contribution.fund_values, &fund_asset).multiply_ratio(amount, fund_amount);
+    let reward_amount = asset_amount(&contribution.bought_amounts, &fund_asset).multiply_ratio(amount, fund_amount);
+    sub_asset_amount(&mut contribution.fund_amounts, &fund_asset, amount)?;
+    sub_asset_amount(&mut contribution.fund_values, &fund_asset, fund_value)?;
+    sub_asset_amount(&mut contribution.bought_amounts, &fund_asset, reward_amount)?;
+
     /* Update rewards amount */
-    let reward_amount = calc_reward_amount(deps.as_ref(), state.clone(), amount)?;
-    recp_info.fund_balance = new_fund_balance;
-    recp_info.reward_balance = recp_info.reward_balance + reward_amount;
-    state.private_sold_amount = state.private_sold_amount + reward_amount;
-    private_sold_fund = private_sold_fund + amount;
+    let mut recp_info = PARTICIPANTS.load(deps.storage, sender.clone())?;
+    let round_sold_fund = ROUND_SOLD_FUNDS.load(deps.storage, (round_id, sender.clone()))?;
+    recp_info.fund_balance -= fund_value;
+    sub_asset_amount(&mut recp_info.fund_balances, &fund_asset, amount)?;
+    recp_info.reward_balance -= reward_amount;
+    sub_asset_amount(&mut recp_info.bought_amounts, &fund_asset, reward_amount)?;
+    sub_asset_amount(&mut state.raised_amounts, &fund_asset, amount)?;
+    sub_asset_amount(&mut state.bought_amounts, &fund_asset, reward_amount)?;
+    let round = &mut state.rounds[round_id as usize];
+    round.sold_amount -= reward_amount;
+
+    /* Dutch auction is no longer sold out */
+    if state.sold_amount() < state.total_rewards_amount {
+        state.clearing_price = None;
+    }
 
     store_state(deps.storage, &state)?;
     PARTICIPANTS.save(deps.storage, sender.clone(), &recp_info)?;
-    PRIVATE_SOLD_FUNDS.save(deps.storage, sender.clone(), &private_sold_fund)?;
+    ROUND_SOLD_FUNDS.save(deps.storage, (round_id, sender.clone()), &(round_sold_fund - fund_value))?;
+    ROUND_CONTRIBUTIONS.save(deps.storage, (round_id, sender.clone()), &contribution)?;
 
-    /* Update vesting */
     let mut messages: Vec<CosmosMsg> = vec![];
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps.api.addr_humanize(&state.vesting)?.to_string(),
-        msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
-            recp: sender.clone(),
-            amount: recp_info.reward_balance.u128().try_into().unwrap(),
-        })?,
-        funds: vec![],
-    }));
+    if let (SaleMode::FixedPrice, Some(vesting)) = (&state.sale_mode, &state.vesting) {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: sender.clone(),
+                amount: vesting_amount(recp_info.reward_balance)?,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    /* Withdrawal fee goes to the treasury */
+    let fee_amount = amount.multiply_ratio(cool_off.fee_bps, FEE_DENOMINATOR);
+    let refund_amount = amount - fee_amount;
+    if !fee_amount.is_zero() {
+        messages.push(fund_asset.transfer_msg(deps.api.addr_humanize(&state.treasury)?.to_string(), fee_amount)?);
+    }
+    if !refund_amount.is_zero() {
+        messages.push(fund_asset.transfer_msg(sender, refund_amount)?);
+    }
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("method", "deposit_private"))
+        .add_attributes(vec![
+            attr("action", "withdraw_contribution"),
+            attr("round_id", round_id.to_string()),
+            attr("refund_amount", refund_amount),
+            attr("fee_amount", fee_amount),
+            attr("reward_amount", reward_amount),
+        ]))
 }
 
 pub fn execute_withdraw_funds(deps: DepsMut, env: Env, info: MessageInfo, receiver: String) -> Result<Response, ContractError> {
-    let state: State = read_state(deps.storage)?;
+    let mut state: State = read_state(deps.storage)?;
     let receiver_addr = deps.api.addr_validate(&receiver)?;
 
     // permission check
@@ -332,24 +1096,62 @@ pub fn execute_withdraw_funds(deps: DepsMut, env: Env, info: MessageInfo, receiv
         return Err(ContractError::Unauthorized {});
     }
 
-    let end_time = state.public_start_time + state.presale_period;
-    if env.block.time.seconds() <= end_time {
-        return Err(ContractError::StillInProgress {  });
+    match state.status {
+        SaleStatus::Pending => return Err(ContractError::NotFinalized {}),
+        SaleStatus::Failed => return Err(ContractError::SaleFailed {}),
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        SaleStatus::Succeeded => {}
     }
 
-    let fund_balance = query_balance(deps.as_ref(), env.contract.address, state.fund_denom.clone())?;
+    let mut withdraw_amounts: Vec<Asset> = vec![];
+    match state.sale_mode {
+        SaleMode::FixedPrice => {
+            for asset in state.accepted_assets.iter() {
+                let fund_balance = query_fund_balance(deps.as_ref(), &asset.info, env.contract.address.clone())?;
+                add_asset_amount(&mut withdraw_amounts, &asset.info, fund_balance);
+            }
+        }
+        // funds paid above the clearing price or left unfilled are kept for the participants to settle
+        SaleMode::DutchAuction { .. } | SaleMode::ProRata => {
+            if state.funds_withdrawn {
+                return Err(ContractError::AlreadyWithdrawn {});
+            }
+            for asset in state.accepted_assets.iter() {
+                let fund_amount = settled_fund_amount(
+                    &state,
+                    asset,
+                    asset_amount(&state.raised_amounts, &asset.info),
+                    asset_amount(&state.bought_amounts, &asset.info),
+                )?.saturating_sub(asset_amount(&state.provided_liquidity, &asset.info));
+                add_asset_amount(&mut withdraw_amounts, &asset.info, fund_amount);
+            }
+            state.funds_withdrawn = true;
+            store_state(deps.storage, &state)?;
+        }
+    }
 
+    /* Split the funds between the project and the treasury */
+    let treasury = deps.api.addr_humanize(&state.treasury)?;
     let mut messages: Vec<CosmosMsg> = vec![];
-    messages.push(CosmosMsg::Bank(BankMsg::Send {
-        to_address: receiver_addr.to_string(),
-        amount: vec![Coin {
-            denom: state.fund_denom.to_string(),
-            amount: fund_balance.clone(),
-        }]
-    }));
+    let mut project_amounts: Vec<String> = vec![];
+    let mut fee_amounts: Vec<String> = vec![];
+    for withdraw in withdraw_amounts.into_iter().filter(|withdraw| !withdraw.amount.is_zero()) {
+        let fee_amount = withdraw.amount.multiply_ratio(state.platform_fee_bps, FEE_DENOMINATOR);
+        let project_amount = withdraw.amount - fee_amount;
+        if !project_amount.is_zero() {
+            messages.push(withdraw.info.transfer_msg(receiver_addr.to_string(), project_amount)?);
+            project_amounts.push(Asset { info: withdraw.info.clone(), amount: project_amount }.to_string());
+        }
+        if !fee_amount.is_zero() {
+            messages.push(withdraw.info.transfer_msg(treasury.to_string(), fee_amount)?);
+            fee_amounts.push(Asset { info: withdraw.info, amount: fee_amount }.to_string());
+        }
+    }
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("method", "withdraw_funds"))
+        .add_attribute("method", "withdraw_funds")
+        .add_attribute("project_amount", project_amounts.join(","))
+        .add_attribute("fee_amount", fee_amounts.join(",")))
 }
 
 pub fn execute_withdraw_unsold_token(deps: DepsMut, env: Env, info: MessageInfo, receiver: String) -> Result<Response, ContractError> {
@@ -361,34 +1163,511 @@ pub fn execute_withdraw_unsold_token(deps: DepsMut, env: Env, info: MessageInfo,
         return Err(ContractError::Unauthorized {});
     }
 
-    let end_time = state.public_start_time + state.presale_period;
-    if env.block.time.seconds() <= end_time {
+    if state.status == SaleStatus::Pending {
+        return Err(ContractError::NotFinalized {});
+    }
+
+    let unsold_amount = query_unsold_amount(deps.as_ref(), &env, &state)?;
+
+    let messages: Vec<CosmosMsg> = vec![inventory_transfer_msg(deps.as_ref(), &state, receiver_addr.to_string(), unsold_amount)?];
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "withdraw_unsold_token"))
+}
+
+pub fn execute_finalize(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+
+    if env.block.time.seconds() <= state.end_time() {
         return Err(ContractError::StillInProgress {  });
     }
+    if state.status != SaleStatus::Pending {
+        return Err(ContractError::AlreadyFinalized {});
+    }
 
-    let reward_balance_info: BalanceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: deps.api.addr_humanize(&state.reward_token)?.to_string(),
-        msg: to_binary(&Cw20QueryMsg::Balance {
-            address: deps.api.addr_humanize(&state.vesting)?.to_string()
-        })?,
-    }))?;
+    let sold_amount = state.sold_amount();
+    state.status = if sold_amount >= state.soft_cap {
+        SaleStatus::Succeeded
+    } else {
+        SaleStatus::Failed
+    };
+    if let SaleMode::DutchAuction { floor_price_multiplier, .. } = state.sale_mode {
+        state.clearing_price = Some(state.clearing_price.unwrap_or(floor_price_multiplier));
+    }
 
-    let sold_amount = state.private_sold_amount + state.public_sold_amount;
-    let unsold_amount = reward_balance_info.balance - Uint128::from(sold_amount);
+    let mut messages: Vec<SubMsg> = vec![];
+    let mut attrs = vec![];
+    if let (SaleStatus::Succeeded, Some(liquidity)) = (&state.status, state.liquidity.clone()) {
+        let (liquidity_messages, liquidity_status) = provide_liquidity(deps.as_ref(), &env, &mut state, &liquidity)?;
+        messages = liquidity_messages;
+        attrs.push(attr("liquidity", liquidity_status));
+    }
+    store_state(deps.storage, &state)?;
 
-    let mut messages: Vec<CosmosMsg> = vec![];
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+    Ok(Response::new().add_submessages(messages).add_attributes(vec![
+        attr("action", "finalize"),
+        attr("sold_amount", sold_amount),
+        attr("succeeded", (state.status == SaleStatus::Succeeded).to_string()),
+    ]).add_attributes(attrs))
+}
+
+/// Account holding the sale inventory, the vesting contract or the presale itself without vesting.
+fn inventory_address(deps: Deps, env: &Env, state: &State) -> StdResult<Addr> {
+    match &state.vesting {
+        Some(vesting) => deps.api.addr_humanize(vesting),
+        None => Ok(env.contract.address.clone()),
+    }
+}
+
+/// Reward tokens left in the inventory that are not owed to the participants.
+fn query_unsold_amount(deps: Deps, env: &Env, state: &State) -> StdResult<Uint128> {
+    // nothing is distributed from a failed or cancelled presale, an oversubscribed one distributes everything
+    let sold_amount = if matches!(state.status, SaleStatus::Failed | SaleStatus::Cancelled) {
+        Uint128::zero()
+    } else {
+        state.sold_amount().min(state.total_rewards_amount)
+    };
+    let balance = query_token_balance(deps, deps.api.addr_humanize(&state.reward_token)?, inventory_address(deps, env, state)?)?;
+    Ok(balance.saturating_sub(sold_amount.saturating_sub(state.claimed_amount)))
+}
+
+/// Transfers reward tokens out of the inventory.
+fn inventory_transfer_msg(deps: Deps, state: &State, recipient: String, amount: Uint128) -> StdResult<CosmosMsg> {
+    let msg = match &state.vesting {
+        Some(vesting) => Cw20ExecuteMsg::TransferFrom {
+            owner: deps.api.addr_humanize(vesting)?.to_string(),
+            recipient,
+            amount,
+        },
+        None => Cw20ExecuteMsg::Transfer { recipient, amount },
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: deps.api.addr_humanize(&state.reward_token)?.to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-            owner: deps.api.addr_humanize(&state.vesting)?.to_string(),
-            recipient: receiver_addr.to_string(),
-            amount: unsold_amount,
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    }))
+}
+
+/// Pairs a share of the raised fund asset with reward tokens at the average sale price.
+/// The reward tokens are taken from the unsold ones in the inventory.
+/// Liquidity is skipped when there is nothing to pair or the pool trades away from the sale price,
+/// the returned status tells which.
+fn provide_liquidity(deps: Deps, env: &Env, state: &mut State, liquidity: &LiquidityConfig) -> Result<(Vec<SubMsg>, &'static str), ContractError> {
+    let asset = state.accepted_assets.iter()
+        .find(|asset| asset.info == liquidity.fund_asset)
+        .cloned()
+        .ok_or(ContractError::InvalidInput {})?;
+    let bought_amount = asset_amount(&state.bought_amounts, &asset.info);
+    let raised_amount = settled_fund_amount(state, &asset, asset_amount(&state.raised_amounts, &asset.info), bought_amount)?;
+    if raised_amount.is_zero() {
+        return Ok((vec![], "nothing_to_pair"));
+    }
+
+    let mut fund_amount = raised_amount.multiply_ratio(liquidity.liquidity_bps, FEE_DENOMINATOR);
+    let mut reward_amount = settled_reward_amount(state, bought_amount).multiply_ratio(fund_amount, raised_amount);
+
+    // only unsold reward tokens can be paired
+    let reward_token = deps.api.addr_humanize(&state.reward_token)?;
+    let unsold_amount = query_unsold_amount(deps, env, state)?;
+    if reward_amount > unsold_amount {
+        fund_amount = fund_amount.multiply_ratio(unsold_amount, reward_amount);
+        reward_amount = unsold_amount;
+    }
+    if fund_amount.is_zero() || reward_amount.is_zero() {
+        return Ok((vec![], "nothing_to_pair"));
+    }
+
+    // anyone can seed the pair before finalization, its price has to match the sale price
+    let pair = deps.api.addr_humanize(&liquidity.pair)?.to_string();
+    let pool = query_pool(deps, pair.clone())?;
+    let pool_amount = |info: AssetInfo| pool.assets.iter().find(|pool_asset| pool_asset.info == info).map(|pool_asset| pool_asset.amount).unwrap_or_default();
+    let pool_fund = pool_amount(AssetInfo::from(&asset.info));
+    let pool_reward = pool_amount(AssetInfo::Token { contract_addr: reward_token.to_string() });
+    if !pool.total_share.is_zero() && !within_slippage(fund_amount, reward_amount, pool_fund, pool_reward)? {
+        return Ok((vec![], "pool_price_mismatch"));
+    }
+
+    let mut messages: Vec<SubMsg> = vec![];
+    if state.vesting.is_some() {
+        messages.push(SubMsg::new(inventory_transfer_msg(deps, state, env.contract.address.to_string(), reward_amount)?));
+    }
+    messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: reward_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+            spender: pair.clone(),
+            amount: reward_amount,
+            expires: None,
         })?,
         funds: vec![],
-    }));
+    })));
+
+    let mut funds: Vec<Coin> = vec![];
+    match &asset.info {
+        FundAsset::Native { denom } => funds.push(Coin { denom: denom.clone(), amount: fund_amount }),
+        FundAsset::Cw20 { contract_addr } => messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.clone(),
+                amount: fund_amount,
+                expires: None,
+            })?,
+            funds: vec![],
+        }))),
+    }
+    messages.push(SubMsg::reply_on_success(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair,
+        msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                PairAsset { info: AssetInfo::from(&asset.info), amount: fund_amount },
+                PairAsset { info: AssetInfo::Token { contract_addr: reward_token.to_string() }, amount: reward_amount },
+            ],
+            slippage_tolerance: Some(Decimal::from_ratio(LIQUIDITY_SLIPPAGE_BPS, FEE_DENOMINATOR)),
+            auto_stake: None,
+            receiver: None,
+        })?,
+        funds,
+    }), PROVIDE_LIQUIDITY_REPLY_ID));
+
+    add_asset_amount(&mut state.provided_liquidity, &asset.info, fund_amount);
+    Ok((messages, "provided"))
+}
+
+/// Whether the pool price deviates from the deposit price by at most LIQUIDITY_SLIPPAGE_BPS, in both directions.
+fn within_slippage(fund_amount: Uint128, reward_amount: Uint128, pool_fund: Uint128, pool_reward: Uint128) -> StdResult<bool> {
+    let deposit_price = Uint256::from(fund_amount).checked_mul(Uint256::from(pool_reward))?;
+    let pool_price = Uint256::from(pool_fund).checked_mul(Uint256::from(reward_amount))?;
+    let max_ratio = Uint256::from(FEE_DENOMINATOR);
+    let min_ratio = Uint256::from(FEE_DENOMINATOR - LIQUIDITY_SLIPPAGE_BPS);
+
+    Ok(deposit_price.checked_mul(min_ratio)? <= pool_price.checked_mul(max_ratio)?
+        && pool_price.checked_mul(min_ratio)? <= deposit_price.checked_mul(max_ratio)?)
+}
+
+pub fn execute_claim_refund(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+    let sender = info.sender.to_string();
+
+    if !matches!(state.status, SaleStatus::Failed | SaleStatus::Cancelled) {
+        return Err(ContractError::RefundNotAvailable {});
+    }
+
+    let recp_info = PARTICIPANTS.may_load(deps.storage, sender.clone())?.unwrap_or_default();
+    if recp_info.fund_balance.is_zero() {
+        return Err(ContractError::NothingToRefund {});
+    }
+
+    PARTICIPANTS.save(deps.storage, sender.clone(), &Participant::default())?;
+    for round_id in 0..state.rounds.len() as u64 {
+        ROUND_SOLD_FUNDS.remove(deps.storage, (round_id, sender.clone()));
+        ROUND_CONTRIBUTIONS.remove(deps.storage, (round_id, sender.clone()));
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for fund in recp_info.fund_balances.iter() {
+        if !fund.amount.is_zero() {
+            messages.push(fund.info.transfer_msg(sender.clone(), fund.amount)?);
+        }
+    }
+    // dutch auction bids and pro-rata contributions are not registered for vesting before settlement,
+    // cancellation clears the vesting recipients
+    if let (SaleMode::FixedPrice, SaleStatus::Failed, Some(vesting)) = (&state.sale_mode, &state.status, &state.vesting) {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: sender.clone(),
+                amount: 0,
+            })?,
+            funds: vec![],
+        }));
+    }
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("method", "withdraw_unsold_token"))
+        .add_attributes(vec![
+            attr("action", "claim_refund"),
+            attr("recipient", sender),
+            attr("fund_value", recp_info.fund_balance),
+        ]))
+}
+
+pub fn execute_cancel_sale(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match state.status {
+        SaleStatus::Pending => {}
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        _ => return Err(ContractError::AlreadyFinalized {}),
+    }
+    state.status = SaleStatus::Cancelled;
+    store_state(deps.storage, &state)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(vesting) = &state.vesting {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::ClearRecipients { start_after: None, limit: None })?,
+            funds: vec![],
+        }));
+    }
+
+    // nothing is sold anymore, the whole inventory goes back to the owner
+    let owner = deps.api.addr_humanize(&state.owner)?;
+    let inventory_amount = query_unsold_amount(deps.as_ref(), &env, &state)?;
+    if !inventory_amount.is_zero() {
+        messages.push(inventory_transfer_msg(deps.as_ref(), &state, owner.to_string(), inventory_amount)?);
+    }
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "cancel_sale"),
+            attr("inventory_amount", inventory_amount),
+        ]))
+}
+
+pub fn execute_clear_vesting(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if state.status != SaleStatus::Cancelled {
+        return Err(ContractError::InvalidInput {});
+    }
+    let vesting = state.vesting.as_ref().ok_or(ContractError::InvalidInput {})?;
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::ClearRecipients { start_after, limit })?,
+            funds: vec![],
+        }))
+        .add_attribute("action", "clear_vesting"))
+}
+
+pub fn execute_settle_bid(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let state: State = read_state(deps.storage)?;
+    let sender = info.sender.to_string();
+
+    // fixed price sales are vested on deposit
+    if state.sale_mode == SaleMode::FixedPrice {
+        return Err(ContractError::InvalidInput {});
+    }
+    match state.status {
+        SaleStatus::Pending => return Err(ContractError::NotFinalized {}),
+        SaleStatus::Failed => return Err(ContractError::SaleFailed {}),
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        SaleStatus::Succeeded => {}
+    }
+
+    let mut recp_info = PARTICIPANTS.may_load(deps.storage, sender.clone())?.unwrap_or_default();
+    if recp_info.reward_balance.is_zero() {
+        return Err(ContractError::NothingToSettle {});
+    }
+    if recp_info.settled {
+        return Err(ContractError::AlreadySettled {});
+    }
+
+    /* Refund the funds paid above the clearing price or left unfilled */
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut fund_balances: Vec<Asset> = vec![];
+    let mut fund_balance = Uint128::zero();
+    for fund in recp_info.fund_balances.iter() {
+        let asset = state.accepted_assets.iter()
+            .find(|asset| asset.info == fund.info)
+            .ok_or(ContractError::Funds {})?;
+        let bought_amount = asset_amount(&recp_info.bought_amounts, &fund.info);
+
+        let cleared_amount = settled_fund_amount(&state, asset, fund.amount, bought_amount)?;
+        let refund_amount = fund.amount - cleared_amount;
+        if !refund_amount.is_zero() {
+            messages.push(fund.info.transfer_msg(sender.clone(), refund_amount)?);
+        }
+        add_asset_amount(&mut fund_balances, &fund.info, cleared_amount);
+        fund_balance += calc_fund_value(asset, cleared_amount);
+    }
+
+    recp_info.fund_balances = fund_balances;
+    recp_info.fund_balance = fund_balance;
+    recp_info.reward_balance = settled_reward_amount(&state, recp_info.reward_balance);
+    recp_info.settled = true;
+    PARTICIPANTS.save(deps.storage, sender.clone(), &recp_info)?;
+
+    /* Update vesting, the tokens are claimed from the presale without it */
+    if let Some(vesting) = &state.vesting {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vesting)?.to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: sender.clone(),
+                amount: vesting_amount(recp_info.reward_balance)?,
+            })?,
+            funds: vec![],
+        }));
+    }
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "settle_bid"),
+            attr("recipient", sender),
+            attr("reward_amount", recp_info.reward_balance),
+        ]))
+}
+
+pub fn execute_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+    let sender = info.sender.to_string();
+
+    // the tokens are distributed by the vesting contract when there is one
+    let claim_start_time = match (&state.vesting, state.claim_start_time) {
+        (None, Some(claim_start_time)) => claim_start_time,
+        _ => return Err(ContractError::InvalidInput {}),
+    };
+    if env.block.time.seconds() < claim_start_time {
+        return Err(ContractError::ClaimNotStarted {});
+    }
+    match state.status {
+        SaleStatus::Pending => return Err(ContractError::NotFinalized {}),
+        SaleStatus::Failed => return Err(ContractError::SaleFailed {}),
+        SaleStatus::Cancelled => return Err(ContractError::SaleCancelled {}),
+        SaleStatus::Succeeded => {}
+    }
+
+    let mut recp_info = PARTICIPANTS.may_load(deps.storage, sender.clone())?.unwrap_or_default();
+    if recp_info.reward_balance.is_zero() || recp_info.claimed {
+        return Err(ContractError::NothingToClaim {});
+    }
+    // dutch auction bids and pro-rata contributions are claimable once settled
+    if state.sale_mode != SaleMode::FixedPrice && !recp_info.settled {
+        return Err(ContractError::NotSettled {});
+    }
+
+    recp_info.claimed = true;
+    state.claimed_amount += recp_info.reward_balance;
+    PARTICIPANTS.save(deps.storage, sender.clone(), &recp_info)?;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(inventory_transfer_msg(deps.as_ref(), &state, sender.clone(), recp_info.reward_balance)?)
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("recipient", sender),
+            attr("amount", recp_info.reward_balance),
+        ]))
+}
+
+pub fn execute_register(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+    let lottery = state.lottery.as_mut().ok_or(ContractError::InvalidInput {})?;
+    let sender = info.sender.to_string();
+
+    if env.block.time.seconds() > lottery.registration_end_time {
+        return Err(ContractError::RegistrationClosed {});
+    }
+    if REGISTRATIONS.has(deps.storage, sender.clone()) {
+        return Err(ContractError::AlreadyRegistered {});
+    }
+
+    let registration_id = lottery.registrants_count;
+    lottery.registrants_count += 1;
+    lottery.registrations_hash = registrations_hash(&lottery.registrations_hash, &sender, env.block.height, env.block.time.seconds());
+    REGISTRANTS.save(deps.storage, registration_id, &sender)?;
+    REGISTRATIONS.save(deps.storage, sender.clone(), &registration_id)?;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register"),
+        attr("registrant", sender),
+        attr("registration_id", registration_id.to_string()),
+    ]))
+}
+
+pub fn execute_reveal_lottery(deps: DepsMut, env: Env, info: MessageInfo, secret: String) -> Result<Response, ContractError> {
+    let mut state: State = read_state(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lottery = state.lottery.as_mut().ok_or(ContractError::InvalidInput {})?;
+    if env.block.time.seconds() <= lottery.registration_end_time {
+        return Err(ContractError::StillInProgress {});
+    }
+    if lottery.draw.is_some() {
+        return Err(ContractError::AlreadyDrawn {});
+    }
+    if env.block.time.seconds() > lottery.reveal_deadline {
+        return Err(ContractError::RevealDeadlinePassed {});
+    }
+    if lottery.commitment != lottery_commitment(&secret) {
+        return Err(ContractError::VerificationFailed {});
+    }
+
+    let (seed, winners_count) = draw_lottery(deps.storage, &env, lottery, secret)?;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "reveal_lottery"),
+        attr("seed", seed),
+        attr("winners_count", winners_count.to_string()),
+    ]))
+}
+
+/// Draws and stores the winners, returning the hex-encoded seed and the number of winners.
+fn draw_lottery(storage: &mut dyn Storage, env: &Env, lottery: &mut Lottery, secret: String) -> StdResult<(String, usize)> {
+    let seed = lottery_seed(&secret, &lottery.registrations_hash);
+    let winners = draw_winners(&seed, lottery.registrants_count, lottery.winners_count);
+    for registration_id in winners.iter() {
+        let winner = REGISTRANTS.load(storage, *registration_id)?;
+        LOTTERY_WINNERS.save(storage, winner, &lottery.allocation)?;
+    }
+
+    lottery.draw = Some(LotteryDraw {
+        secret,
+        height: env.block.height,
+        time: env.block.time.seconds(),
+        seed: hex::encode(seed),
+    });
+    Ok((hex::encode(seed), winners.len()))
+}
+
+/// Fund amount kept by the presale out of a contribution once settled, the rest goes back to the participant.
+fn settled_fund_amount(state: &State, asset: &AcceptedAsset, fund_amount: Uint128, bought_amount: Uint128) -> StdResult<Uint128> {
+    match state.sale_mode {
+        SaleMode::FixedPrice => Ok(fund_amount),
+        SaleMode::DutchAuction { .. } => {
+            let clearing_price = state.clearing_price.unwrap_or_default();
+            Ok(calc_fund_amount(state, asset, clearing_price, bought_amount)?.min(fund_amount))
+        }
+        SaleMode::ProRata => {
+            let requested_amount = state.sold_amount();
+            if requested_amount <= state.total_rewards_amount {
+                return Ok(fund_amount);
+            }
+            // round the unfilled part down so that settlements never exceed the balance
+            let unfilled_amount = fund_amount.multiply_ratio(requested_amount - state.total_rewards_amount, requested_amount);
+            Ok(fund_amount - unfilled_amount)
+        }
+    }
+}
+
+/// Reward token amount filled out of the requested amount once settled.
+fn settled_reward_amount(state: &State, reward_amount: Uint128) -> Uint128 {
+    let requested_amount = state.sold_amount();
+    if state.sale_mode == SaleMode::ProRata && requested_amount > state.total_rewards_amount {
+        reward_amount.multiply_ratio(state.total_rewards_amount, requested_amount)
+    } else {
+        reward_amount
+    }
 }
 
 /************************************ Query *************************************/
@@ -397,35 +1676,48 @@ pub fn execute_withdraw_unsold_token(deps: DepsMut, env: Env, info: MessageInfo,
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ParticipantsCount {} => to_binary(&query_count(deps)?),
-        QueryMsg::GetParticipants { page, limit } => to_binary(&query_participants(deps, page, limit)?),
+        QueryMsg::GetParticipants { start_after, limit, order_by } => to_binary(&query_participants(deps, start_after, limit, order_by)?),
         QueryMsg::GetParticipant { user } => to_binary(&query_participant(deps, user)?),
         QueryMsg::GetSaleStatus { } => to_binary( &query_sale_status(deps)? ),
-        QueryMsg::PresaleInfo { } => to_binary( &query_presale_info(deps)? )
+        QueryMsg::PresaleInfo { } => to_binary( &query_presale_info(deps)? ),
+        QueryMsg::Rounds { } => to_binary( &query_rounds(deps)? ),
+        QueryMsg::CurrentTranche { } => to_binary( &query_current_tranche(deps)? ),
+        QueryMsg::Oversubscription { } => to_binary( &query_oversubscription(deps)? ),
+        QueryMsg::Lottery { } => to_binary( &query_lottery(deps)? ),
+        QueryMsg::LotteryWinners { start_after, limit } => to_binary(&query_lottery_winners(deps, start_after, limit)?),
+        QueryMsg::VerifyLottery { } => to_binary( &query_verify_lottery(deps)? ),
+        QueryMsg::Whitelist { id } => to_binary(&query_whitelist(deps, id)?),
+        QueryMsg::Whitelists { start_after, limit } => to_binary(&query_whitelists(deps, start_after, limit)?),
+        QueryMsg::TicketNonce { nonce } => to_binary(&query_ticket_nonce(deps, nonce)?),
     }
 }
 
 fn query_count(deps: Deps) -> StdResult<ParticipantsCountResponse> {
-    let state: State = read_state(deps.storage)?;
-    Ok(ParticipantsCountResponse { count: state.userlist.len() as u64 })
+    let count = PARTICIPANTS_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    Ok(ParticipantsCountResponse { count })
 }
 
-fn query_participants(deps: Deps, page: u64, limit: u64) -> StdResult<GetParticipantsResponse> {
-    let state: State = read_state(deps.storage)?;
-
-    let start = (page * limit) as usize;
-    let end = (page * limit + limit) as usize;
-
-    Ok(GetParticipantsResponse { participants: state.userlist[start..end].to_vec() })
+fn query_participants(deps: Deps, start_after: Option<String>, limit: Option<u32>, order_by: Option<OrderBy>) -> StdResult<GetParticipantsResponse> {
+    let participants = read_participants(deps.storage, start_after, limit, order_by)?
+        .into_iter()
+        .map(|(address, data)| ParticipantInfo { address, data })
+        .collect();
+    Ok(GetParticipantsResponse { participants })
 }
 
 fn query_participant(deps: Deps, user: String) -> StdResult<GetParticipantResponse> {
-    let data = PARTICIPANTS.load(deps.storage, user).unwrap_or(Participant { fund_balance: Uint128::zero(), reward_balance: Uint128::zero() });
+    let data = PARTICIPANTS.load(deps.storage, user).unwrap_or_default();
     Ok(GetParticipantResponse { data })
 }
 
 fn query_sale_status(deps: Deps) -> StdResult<GetSaleStatusResponse> {
     let state: State = read_state(deps.storage)?;
-    Ok(GetSaleStatusResponse { private_sold_amount: state.private_sold_amount, public_sold_amount: state.public_sold_amount })
+    Ok(GetSaleStatusResponse {
+        sold_amount: state.sold_amount(),
+        raised_amounts: state.raised_amounts,
+        status: state.status,
+        clearing_price: state.clearing_price,
+    })
 }
 
 fn query_presale_info(deps: Deps) -> StdResult<PresaleInfoResponse> {
@@ -433,11 +1725,144 @@ fn query_presale_info(deps: Deps) -> StdResult<PresaleInfoResponse> {
     Ok(PresaleInfoResponse {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
         accuracy: Uint128::from(ACCURACY),
-        exchange_rate: state.exchange_rate,
-        presale_period: state.presale_period,
-        public_start_time: state.public_start_time,
-        private_start_time: state.private_start_time,
-        total_rewards_amount: state.total_rewards_amount
+        start_time: state.start_time(),
+        end_time: state.end_time(),
+        accepted_assets: state.accepted_assets,
+        reward_decimals: state.reward_decimals,
+        total_rewards_amount: state.total_rewards_amount,
+        soft_cap: state.soft_cap,
+        tranches: state.tranches,
+        sale_mode: state.sale_mode,
+        vesting: state.vesting.map(|vesting| deps.api.addr_humanize(&vesting)).transpose()?.map(|vesting| vesting.to_string()),
+        claim_start_time: state.claim_start_time,
+        treasury: deps.api.addr_humanize(&state.treasury)?.to_string(),
+        platform_fee_bps: state.platform_fee_bps,
+        liquidity: state.liquidity.map(|liquidity| -> StdResult<LiquidityInfo> {
+            Ok(LiquidityInfo {
+                pair: deps.api.addr_humanize(&liquidity.pair)?.to_string(),
+                fund_asset: liquidity.fund_asset,
+                liquidity_bps: liquidity.liquidity_bps,
+                locking: liquidity.locking.map(|locking| deps.api.addr_humanize(&locking)).transpose()?.map(|locking| locking.to_string()),
+            })
+        }).transpose()?,
+        provided_liquidity: state.provided_liquidity,
+        staking_tiers: state.staking_tiers.map(|staking_tiers| -> StdResult<StakingTiersInfo> {
+            Ok(StakingTiersInfo {
+                staking: deps.api.addr_humanize(&staking_tiers.staking)?.to_string(),
+                tiers: staking_tiers.tiers,
+            })
+        }).transpose()?,
+        lock_requirement: state.lock_requirement.map(|lock_requirement| -> StdResult<LockRequirementInfo> {
+            Ok(LockRequirementInfo {
+                locking: deps.api.addr_humanize(&lock_requirement.locking)?.to_string(),
+                min_amount: lock_requirement.min_amount,
+                snapshot_time: lock_requirement.snapshot_time,
+            })
+        }).transpose()?,
+        cool_off: state.cool_off,
+        whitelist_leaf_format: state.whitelist_leaf_format,
+        kyc_signer: state.kyc_signer,
     })
 }
 
+fn query_rounds(deps: Deps) -> StdResult<RoundsResponse> {
+    let state: State = read_state(deps.storage)?;
+    Ok(RoundsResponse { rounds: state.rounds })
+}
+
+fn query_current_tranche(deps: Deps) -> StdResult<CurrentTrancheResponse> {
+    let state: State = read_state(deps.storage)?;
+    let sold_amount = state.sold_amount();
+
+    Ok(match state.current_tranche(sold_amount) {
+        Some((index, remaining_amount)) => CurrentTrancheResponse {
+            tranche_id: Some(index as u64),
+            price_multiplier: state.tranches[index].price_multiplier,
+            remaining_amount,
+        },
+        None => CurrentTrancheResponse {
+            tranche_id: None,
+            price_multiplier: Decimal::one(),
+            remaining_amount: state.total_rewards_amount.saturating_sub(sold_amount),
+        },
+    })
+}
+
+fn query_oversubscription(deps: Deps) -> StdResult<OversubscriptionResponse> {
+    let state: State = read_state(deps.storage)?;
+    Ok(OversubscriptionResponse {
+        requested_amount: state.sold_amount(),
+        total_rewards_amount: state.total_rewards_amount,
+        ratio: state.oversubscription_ratio(),
+    })
+}
+
+fn query_lottery(deps: Deps) -> StdResult<LotteryResponse> {
+    let state: State = read_state(deps.storage)?;
+    Ok(LotteryResponse { lottery: state.lottery })
+}
+
+fn whitelist_response(id: String, whitelist: Whitelist) -> WhitelistResponse {
+    WhitelistResponse {
+        id,
+        merkle_root: whitelist.merkle_root,
+        round_ids: whitelist.round_ids,
+        cap: whitelist.cap,
+        sold_amount: whitelist.sold_amount,
+    }
+}
+
+fn query_whitelist(deps: Deps, id: String) -> StdResult<WhitelistResponse> {
+    let whitelist = WHITELISTS.load(deps.storage, id.clone())?;
+    Ok(whitelist_response(id, whitelist))
+}
+
+fn query_whitelists(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<WhitelistsResponse> {
+    let whitelists = read_whitelists(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, whitelist)| whitelist_response(id, whitelist))
+        .collect();
+    Ok(WhitelistsResponse { whitelists })
+}
+
+fn query_ticket_nonce(deps: Deps, nonce: u64) -> StdResult<TicketNonceResponse> {
+    let redemption = USED_TICKET_NONCES.may_load(deps.storage, nonce)?;
+    Ok(TicketNonceResponse {
+        used_by: redemption.as_ref().map(|redemption| redemption.account.clone()),
+        redeemed_value: redemption.map(|redemption| redemption.redeemed_value).unwrap_or_default(),
+    })
+}
+
+fn query_lottery_winners(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<LotteryWinnersResponse> {
+    let winners = read_lottery_winners(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(address, allocation)| LotteryWinnerInfo { address, allocation })
+        .collect();
+    Ok(LotteryWinnersResponse { winners })
+}
+
+fn query_verify_lottery(deps: Deps) -> StdResult<VerifyLotteryResponse> {
+    let state: State = read_state(deps.storage)?;
+    let lottery = match state.lottery {
+        Some(lottery) => lottery,
+        None => return Ok(VerifyLotteryResponse { valid: false }),
+    };
+    let draw = match &lottery.draw {
+        Some(draw) => draw,
+        None => return Ok(VerifyLotteryResponse { valid: false }),
+    };
+
+    // the revealed secret has to match the commitment
+    let seed = lottery_seed(&draw.secret, &lottery.registrations_hash);
+    if lottery.commitment != lottery_commitment(&draw.secret) || hex::encode(seed) != draw.seed {
+        return Ok(VerifyLotteryResponse { valid: false });
+    }
+
+    for registration_id in draw_winners(&seed, lottery.registrants_count, lottery.winners_count) {
+        let winner = REGISTRANTS.load(deps.storage, registration_id)?;
+        if !LOTTERY_WINNERS.has(deps.storage, winner) {
+            return Ok(VerifyLotteryResponse { valid: false });
+        }
+    }
+    Ok(VerifyLotteryResponse { valid: true })
+}
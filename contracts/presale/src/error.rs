@@ -1,6 +1,7 @@
 use cosmwasm_std::StdError;
 use hex::FromHexError;
 use thiserror::Error;
+use versioning::MigrationError;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -16,11 +17,14 @@ pub enum ContractError {
     #[error("Invalid input")]
     InvalidInput {},
 
-    #[error("Public Not In Progress")]
-    PublicNotInProgress {},
+    #[error("Round Not Found")]
+    RoundNotFound {},
 
-    #[error("Private Not In Progress")]
-    PrivateNotInProgress {},
+    #[error("Sale Started")]
+    SaleStarted {},
+
+    #[error("Round Not In Progress")]
+    RoundNotInProgress {},
 
     #[error("Still In Progress")]
     StillInProgress {},
@@ -28,9 +32,49 @@ pub enum ContractError {
     #[error("Not Whitelisted")]
     NotWhitelisted {},
 
+    #[error("Not Eligible")]
+    NotEligible {},
+
+    #[error("Registration Closed")]
+    RegistrationClosed {},
+
+    #[error("Already Registered")]
+    AlreadyRegistered {},
+
+
+    #[error("Already Drawn")]
+    AlreadyDrawn {},
+
+    #[error("Reveal Deadline Passed")]
+    RevealDeadlinePassed {},
+
+    #[error("Lottery Cancelled")]
+    LotteryCancelled {},
+
     #[error("Exceed Allocation")]
     ExceedAllocation {},
 
+    #[error("Legacy Whitelist")]
+    LegacyWhitelist {},
+
+    #[error("Whitelist Not Found")]
+    WhitelistNotFound {},
+
+    #[error("Whitelist Exists")]
+    WhitelistExists {},
+
+    #[error("Whitelist Cap Reached")]
+    WhitelistCapReached {},
+
+    #[error("Invalid Ticket")]
+    InvalidTicket {},
+
+    #[error("Ticket Expired")]
+    TicketExpired {},
+
+    #[error("Nonce Already Used")]
+    NonceUsed {},
+
     #[error("Wrong length")]
     WrongLength {},
 
@@ -38,5 +82,56 @@ pub enum ContractError {
     VerificationFailed {},
 
     #[error("Funds not paid")]
-    Funds {}    
+    Funds {},
+
+    #[error("Hard Cap Reached")]
+    HardCapReached {},
+
+    #[error("Already Finalized")]
+    AlreadyFinalized {},
+
+    #[error("Not Finalized")]
+    NotFinalized {},
+
+    #[error("Sale Failed")]
+    SaleFailed {},
+
+    #[error("Refund Not Available")]
+    RefundNotAvailable {},
+
+    #[error("Nothing To Refund")]
+    NothingToRefund {},
+
+    #[error("Sale Cancelled")]
+    SaleCancelled {},
+
+    #[error("Nothing To Settle")]
+    NothingToSettle {},
+
+    #[error("Nothing To Claim")]
+    NothingToClaim {},
+
+    #[error("Not Settled")]
+    NotSettled {},
+
+    #[error("Claim Not Started")]
+    ClaimNotStarted {},
+
+    #[error("Cool-Off Not Available")]
+    CoolOffNotAvailable {},
+
+    #[error("Exceed Vesting Amount")]
+    ExceedVestingAmount {},
+
+    #[error("Exceed Contribution")]
+    ExceedContribution {},
+
+    #[error("Already Settled")]
+    AlreadySettled {},
+
+    #[error("Already Withdrawn")]
+    AlreadyWithdrawn {},
+
+    #[error("{0}")]
+    Migration(#[from] MigrationError),
 }
@@ -3,7 +3,13 @@ pub mod msg;
 pub mod state;
 pub mod error;
 pub mod querier;
+pub mod pair;
+pub mod lottery;
+pub mod merkle;
+pub mod ticket;
 pub mod types;
 
+#[cfg(test)]
+mod mock_querier;
 #[cfg(test)]
 pub mod testing;
\ No newline at end of file
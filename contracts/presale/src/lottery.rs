@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use sha2::{Digest, Sha256};
+
+/// Hex-encoded sha256 hash of the secret, committed by the owner when the presale is created.
+pub fn lottery_commitment(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Running hash of the registrations, chaining each registrant with the block data of its registration.
+pub fn registrations_hash(previous: &str, registrant: &str, height: u64, time: u64) -> String {
+    let hash = Sha256::new()
+        .chain_update(previous.as_bytes())
+        .chain_update(registrant.as_bytes())
+        .chain_update(height.to_be_bytes())
+        .chain_update(time.to_be_bytes())
+        .finalize();
+    hex::encode(hash)
+}
+
+/// Seed of the draw, the revealed secret combined with the registrations hash.
+/// Both are fixed once registration closes, so the time of the reveal has no effect on the draw.
+/// There is no seed without the secret, registrants could compute it from the public registrations.
+pub fn lottery_seed(secret: &str, registrations_hash: &str) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(secret.as_bytes())
+        .chain_update(registrations_hash.as_bytes())
+        .finalize()
+        .into()
+}
+
+/// Draws the registration ids of the winners with a partial Fisher-Yates shuffle of the registrants.
+/// Every registrant wins when there are no more registrants than winners.
+pub fn draw_winners(seed: &[u8; 32], registrants_count: u64, winners_count: u64) -> Vec<u64> {
+    // only the shuffled positions are kept, the others still hold their own id
+    let mut shuffled: HashMap<u64, u64> = HashMap::new();
+    let mut winners = vec![];
+    for i in 0..winners_count.min(registrants_count) {
+        let hash = Sha256::new()
+            .chain_update(seed)
+            .chain_update(i.to_be_bytes())
+            .finalize();
+        let random = u64::from_be_bytes(hash[..8].try_into().unwrap());
+        let j = i + random % (registrants_count - i);
+
+        let winner = *shuffled.get(&j).unwrap_or(&j);
+        let current = *shuffled.get(&i).unwrap_or(&i);
+        shuffled.insert(j, current);
+        winners.push(winner);
+    }
+    winners
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::Uint128;
+use sha2::{Digest, Sha256};
+
+use crate::state::LeafFormat;
+
+/// Domain tag of the versioned whitelist leaves, so that they cannot be replayed as other sha256 preimages.
+pub const WHITELIST_LEAF_DOMAIN: &str = "presale/whitelist/v1";
+
+/// Sale a v1 whitelist leaf or an allocation ticket is bound to.
+pub struct LeafContext<'a> {
+    pub chain_id: &'a str,
+    pub contract: &'a str,
+    pub round_id: u64,
+}
+
+/// Hash of the whitelist entry of `sender` with `allocation` in the given leaf format.
+///
+/// The plain leaf hashes the sender concatenated with the decimal allocation, so that an address ending in digits
+/// can collide with another entry. It is only kept for the sales created with it.
+/// The v1 leaf hashes the domain tag, chain id, presale address, round id, sender and allocation,
+/// strings prefixed by their big-endian u32 byte length and numbers as fixed-width big-endian integers.
+pub fn whitelist_leaf(format: &LeafFormat, ctx: &LeafContext, sender: &str, allocation: Uint128) -> [u8; 32] {
+    match format {
+        LeafFormat::Plain => Sha256::digest(format!("{}{}", sender, allocation).as_bytes()).into(),
+        LeafFormat::V1 => {
+            let mut message = vec![];
+            push_prefixed(&mut message, WHITELIST_LEAF_DOMAIN);
+            push_prefixed(&mut message, ctx.chain_id);
+            push_prefixed(&mut message, ctx.contract);
+            message.extend_from_slice(&ctx.round_id.to_be_bytes());
+            push_prefixed(&mut message, sender);
+            message.extend_from_slice(&allocation.u128().to_be_bytes());
+            Sha256::digest(&message).into()
+        }
+    }
+}
+
+/// Appends the string field prefixed by its big-endian u32 byte length.
+pub(crate) fn push_prefixed(message: &mut Vec<u8>, field: &str) {
+    message.extend_from_slice(&(field.len() as u32).to_be_bytes());
+    message.extend_from_slice(field.as_bytes());
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use locking::msg::{LockInfoResponse, QueryMsg as LockingQueryMsg};
+use locking::state::LockInfo;
+use staking::msg::{QueryMsg as StakingQueryMsg, StakerInfoResponse};
+
+use crate::pair::{PairInfo, PoolResponse, QueryMsg as PairQueryMsg};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+        custom_query_type: PhantomData,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    token_querier: TokenQuerier,
+    pair_querier: PairQuerier,
+    staking_querier: StakingQuerier,
+    locking_querier: LockingQuerier,
+}
+
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    // token contract => holder => balance
+    balances: HashMap<String, HashMap<String, Uint128>>,
+    decimals: HashMap<String, u8>,
+}
+
+#[derive(Clone, Default)]
+pub struct PairQuerier {
+    // pair contract => pair info
+    pairs: HashMap<String, PairInfo>,
+    // pair contract => pool reserves, empty when not set
+    pools: HashMap<String, PoolResponse>,
+}
+
+#[derive(Clone, Default)]
+pub struct StakingQuerier {
+    // staking contract => staker => bonded amount
+    bond_amounts: HashMap<String, HashMap<String, Uint128>>,
+}
+
+#[derive(Clone, Default)]
+pub struct LockingQuerier {
+    // locking contract => account => lock info
+    lock_infos: HashMap<String, HashMap<String, LockInfo>>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if let Ok(PairQueryMsg::Pair {}) = from_binary(msg) {
+                    return match self.pair_querier.pairs.get(contract_addr) {
+                        Some(pair_info) => SystemResult::Ok(ContractResult::from(to_binary(pair_info))),
+                        None => SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.clone(),
+                        }),
+                    };
+                }
+
+                if let Ok(PairQueryMsg::Pool {}) = from_binary(msg) {
+                    let pool = self.pair_querier.pools.get(contract_addr).cloned().unwrap_or(PoolResponse {
+                        assets: vec![],
+                        total_share: Uint128::zero(),
+                    });
+                    return SystemResult::Ok(ContractResult::from(to_binary(&pool)));
+                }
+
+                if let Ok(StakingQueryMsg::StakerInfo { staker, .. }) = from_binary(msg) {
+                    let bond_amount = self
+                        .staking_querier
+                        .bond_amounts
+                        .get(contract_addr)
+                        .and_then(|bond_amounts| bond_amounts.get(&staker))
+                        .cloned()
+                        .unwrap_or_default();
+                    return SystemResult::Ok(ContractResult::from(to_binary(&StakerInfoResponse {
+                        staker,
+                        reward_index: Decimal::zero(),
+                        bond_amount,
+                        pending_reward: Uint128::zero(),
+                    })));
+                }
+
+                if let Ok(LockingQueryMsg::LockInfo { address }) = from_binary(msg) {
+                    let info = self
+                        .locking_querier
+                        .lock_infos
+                        .get(contract_addr)
+                        .and_then(|lock_infos| lock_infos.get(&address))
+                        .cloned()
+                        .unwrap_or(LockInfo { amount: Uint128::zero(), last_locked_time: 0, last_unlocked_time: 0 });
+                    return SystemResult::Ok(ContractResult::from(to_binary(&LockInfoResponse {
+                        address,
+                        info,
+                        penalty: Uint128::zero(),
+                    })));
+                }
+
+                match from_binary(msg) {
+                    Ok(Cw20QueryMsg::TokenInfo {}) => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&TokenInfoResponse {
+                            name: "mock".to_string(),
+                            symbol: "MOCK".to_string(),
+                            decimals: *self.token_querier.decimals.get(contract_addr).unwrap_or(&6u8),
+                            total_supply: Uint128::zero(),
+                        })))
+                    }
+                    Ok(Cw20QueryMsg::Balance { address }) => {
+                        let balance = self
+                            .token_querier
+                            .balances
+                            .get(contract_addr)
+                            .and_then(|balances| balances.get(&address))
+                            .cloned()
+                            .unwrap_or_default();
+                        SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse { balance })))
+                    }
+                    _ => panic!("query not mocked"),
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            token_querier: TokenQuerier::default(),
+            pair_querier: PairQuerier::default(),
+            staking_querier: StakingQuerier::default(),
+            locking_querier: LockingQuerier::default(),
+        }
+    }
+
+    pub fn with_token_balances(&mut self, balances: &[(&str, &[(&str, Uint128)])]) {
+        for (contract_addr, balances) in balances.iter() {
+            let contract_balances = self
+                .token_querier
+                .balances
+                .entry(contract_addr.to_string())
+                .or_default();
+            for (addr, balance) in balances.iter() {
+                contract_balances.insert(addr.to_string(), *balance);
+            }
+        }
+    }
+
+    pub fn with_pair(&mut self, pair_info: PairInfo) {
+        self.pair_querier
+            .pairs
+            .insert(pair_info.contract_addr.clone(), pair_info);
+    }
+
+    pub fn with_pool(&mut self, pair_contract: &str, pool: PoolResponse) {
+        self.pair_querier.pools.insert(pair_contract.to_string(), pool);
+    }
+
+    pub fn with_bond_amounts(&mut self, bond_amounts: &[(&str, &[(&str, Uint128)])]) {
+        for (contract_addr, bond_amounts) in bond_amounts.iter() {
+            let contract_bond_amounts = self
+                .staking_querier
+                .bond_amounts
+                .entry(contract_addr.to_string())
+                .or_default();
+            for (addr, bond_amount) in bond_amounts.iter() {
+                contract_bond_amounts.insert(addr.to_string(), *bond_amount);
+            }
+        }
+    }
+
+    pub fn with_lock_infos(&mut self, lock_infos: &[(&str, &[(&str, LockInfo)])]) {
+        for (contract_addr, lock_infos) in lock_infos.iter() {
+            let contract_lock_infos = self
+                .locking_querier
+                .lock_infos
+                .entry(contract_addr.to_string())
+                .or_default();
+            for (addr, lock_info) in lock_infos.iter() {
+                contract_lock_infos.insert(addr.to_string(), lock_info.clone());
+            }
+        }
+    }
+
+    pub fn with_token_decimals(&mut self, contract_addr: &str, decimals: u8) {
+        self.token_querier
+            .decimals
+            .insert(contract_addr.to_string(), decimals);
+    }
+}
@@ -1,22 +1,139 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{Participant, AlloInfo};
+use crate::state::{AcceptedAsset, AllocationType, CoolOff, KycSigner, LeafFormat, Lottery, Participant, Round, SaleMode, SaleStatus, StakingTier, Tranche};
+use crate::types::{Asset, FundAsset, OrderBy};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
-    pub fund_denom: String,
+    pub accepted_assets: Vec<AcceptedAssetInfo>,
     pub reward_token: String,
-    pub vesting: String,
-    pub whitelist_merkle_root: String,
+    /// Without vesting, the presale holds the sale inventory and participants claim their tokens
+    /// from claim_start_time once the presale succeeded.
+    pub vesting: Option<String>,
+    pub claim_start_time: Option<u64>,
+
+    pub rounds: Vec<RoundInfo>,
+
+    pub total_rewards_amount: Uint128,
+    pub soft_cap: Uint128,
+    /// Price tranches in selling order, their amounts must add up to total_rewards_amount.
+    /// Empty to sell every token at the base price.
+    pub tranches: Vec<Tranche>,
+    /// Dutch auctions require no tranches and a price multiplier of 1 in every round.
+    /// Pro-rata sales require no tranches.
+    pub sale_mode: SaleMode,
+
+    pub treasury: String,
+    /// Share of the raised funds paid to the treasury on withdrawal, in basis points.
+    pub platform_fee_bps: u64,
+    /// Liquidity provided on the DEX by finalization when the presale succeeds.
+    /// It is skipped when the pair was seeded more than 1% away from the sale price.
+    pub liquidity: Option<LiquidityInfo>,
+    /// Required by staking rounds, which allocate by the amount bonded in the staking contract.
+    pub staking_tiers: Option<StakingTiersInfo>,
+    /// Only accounts with enough tokens in the locking contract can participate.
+    pub lock_requirement: Option<LockRequirementInfo>,
+    /// Required by lottery rounds, which are open to the winners of the draw.
+    pub lottery: Option<LotteryInfo>,
+    /// Participants can withdraw their contributions to a round for a period after their first deposit into it,
+    /// paying a fee to the treasury.
+    pub cool_off: Option<CoolOff>,
+    /// Encoding of the whitelist merkle leaves, v1 when not set.
+    /// The v1 leaves are bound to the chain, the presale and the round.
+    pub whitelist_leaf_format: Option<LeafFormat>,
+    /// Named whitelists, deposits into guaranteed and fcfs rounds can give the list their proof belongs to.
+    /// Deposits without a list are verified against the merkle root of the round.
+    pub whitelists: Vec<WhitelistInfo>,
+    /// Signer of the allocation tickets accepted in guaranteed and fcfs rounds instead of merkle proofs.
+    /// Rounds without a merkle root then only accept deposits with a ticket.
+    pub kyc_signer: Option<KycSigner>,
+}
+
+/// Allocation granted by the KYC signer, see `ticket::ticket_message` for the signed message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllocationTicket {
+    pub expires_at: u64,
+    /// The nonce is bound to the first account redeeming it, which can deposit with it until the allocation is filled.
+    pub nonce: u64,
+    pub signature: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistInfo {
+    pub id: String,
+    /// MerkleRoot is hex-encoded merkle root.
+    pub merkle_root: String,
+    /// Guaranteed and fcfs rounds open to the list.
+    pub round_ids: Vec<u64>,
+    /// Max reward token amount sold to the list.
+    pub cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryInfo {
+    pub registration_end_time: u64,
+    /// The owner has to reveal the secret by this time, otherwise the lottery rounds are cancelled.
+    /// It has to pass before the first lottery round starts.
+    pub reveal_deadline: u64,
+    pub winners_count: u64,
+    /// Max fund value per winner.
+    pub allocation: Uint128,
+    /// Hex-encoded sha256 hash of the owner secret.
+    pub commitment: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockRequirementInfo {
+    pub locking: String,
+    pub min_amount: Uint128,
+    /// The minimum amount has to be locked since this time.
+    pub snapshot_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingTiersInfo {
+    pub staking: String,
+    /// Tiers by strictly ascending minimum bonded amount.
+    /// Weighted tiers require a max allocation in every staking round.
+    pub tiers: Vec<StakingTier>,
+}
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidityInfo {
+    /// Astroport-style pair of the fund asset and the reward token.
+    pub pair: String,
+    pub fund_asset: FundAsset,
+    /// Share of the raised fund asset paired with reward tokens at the average sale price, in basis points.
+    pub liquidity_bps: u64,
+    /// Locking contract the LP tokens are locked in, they are sent to the owner when not set.
+    pub locking: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AcceptedAssetInfo {
+    pub info: FundAsset,
     pub exchange_rate: Uint128,
-    pub private_start_time: u64,
-    pub public_start_time: u64,
-    pub presale_period: u64,
+}
 
-    pub total_rewards_amount: Uint128
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundDecimals {
+    pub info: FundAsset,
+    pub decimals: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundInfo {
+    pub name: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub price_multiplier: Decimal,
+    pub whitelist_merkle_root: String,
+    pub allocation_type: AllocationType,
+    pub max_allocation: Option<Uint128>,
+    pub cap: Option<Uint128>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -25,25 +142,44 @@ pub struct MigrateMsg {}
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
     TransferOwnerShip {
         new_owner: String
     },
     SetMerkleRoot {
+        round_id: u64,
         /// MerkleRoot is hex-encoded merkle root.
         merkle_root: String,
     },
-    UpdatePresaleInfo {
-        new_private_start_time: u64,
-        new_public_start_time: u64,
-        new_presale_period: u64
+    UpdateRound {
+        round_id: u64,
+        new_start_time: u64,
+        new_end_time: u64
+    },
+    /// Overrides the decimals queried at instantiation before the sale starts.
+    /// Native fund assets default to 6 decimals, IBC assets may use more.
+    UpdateDecimals {
+        reward_decimals: Option<u32>,
+        fund_decimals: Vec<FundDecimals>,
     },
+    /// Deposit of native fund tokens into the given round.
+    /// Allocation and proof are ignored by public and staking rounds.
+    /// The proof is verified against the given whitelist, or the merkle root of the round without one.
+    /// A ticket signed by the KYC signer for the allocation replaces the proof, it cannot be combined with a whitelist.
     Deposit {
-        allo_info: AlloInfo,
+        round_id: u64,
+        allocation: Uint128,
         proof: Vec<String>,
+        whitelist_id: Option<String>,
+        ticket: Option<AllocationTicket>,
     },
-    DepositPrivateSale {
-        allo_info: AlloInfo,
-        proof: Vec<String>,
+    /// Replaces the KYC signer, none to stop accepting tickets.
+    SetKycSigner {
+        signer: Option<KycSigner>,
+    },
+    /// Adds a named whitelist, the existing lists and their proofs are left unchanged.
+    AddWhitelist {
+        whitelist: WhitelistInfo,
     },
     WithdrawFunds {
         receiver: String,
@@ -51,6 +187,50 @@ pub enum ExecuteMsg {
     WithdrawUnsoldToken {
         receiver: String,
     },
+    /// Withdraws an amount of the fund asset deposited into the given round during the cool-off period.
+    /// The bought tokens are given back at the participant's average price for the asset.
+    WithdrawContribution {
+        round_id: u64,
+        asset: FundAsset,
+        amount: Uint128,
+    },
+    /// Settles the presale as succeeded or failed once it has ended.
+    Finalize {},
+    /// Returns the participant's funds when the presale failed or was cancelled.
+    ClaimRefund {},
+    /// Aborts the presale before finalization, clearing the first page of vesting recipients
+    /// and returning the whole inventory to the owner.
+    CancelSale {},
+    /// Clears the next page of vesting recipients of a cancelled presale.
+    ClearVesting {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Settles a dutch auction bid or a pro-rata contribution once the presale succeeded.
+    /// Refunds the funds paid above the clearing price or left unfilled by an oversubscribed sale
+    /// and registers the bought tokens for vesting, or makes them claimable without it.
+    SettleBid {},
+    /// Transfers the bought tokens of the participant when the presale has no vesting contract.
+    Claim {},
+    /// Registers the sender for the lottery draw.
+    Register {},
+    /// Reveals the committed secret once registration has closed and draws the winners.
+    RevealLottery {
+        secret: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Deposit of a cw20 fund token into the given presale round.
+    Deposit {
+        round_id: u64,
+        allocation: Uint128,
+        proof: Vec<String>,
+        whitelist_id: Option<String>,
+        ticket: Option<AllocationTicket>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -59,13 +239,56 @@ pub enum QueryMsg {
     ParticipantsCount {},
     GetSaleStatus {},
     GetParticipants {
-        page: u64,
-        limit: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order_by: Option<OrderBy>,
     },
     GetParticipant {
         user: String,
     },
-    PresaleInfo {}
+    PresaleInfo {},
+    Rounds {},
+    CurrentTranche {},
+    Oversubscription {},
+    Lottery {},
+    LotteryWinners {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Draws the winners again from the revealed secret and checks them against the stored ones.
+    VerifyLottery {},
+    Whitelist {
+        id: String,
+    },
+    Whitelists {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    TicketNonce {
+        nonce: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistResponse {
+    pub id: String,
+    pub merkle_root: String,
+    pub round_ids: Vec<u64>,
+    pub cap: Option<Uint128>,
+    pub sold_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketNonceResponse {
+    /// Account that redeemed the ticket, none while the nonce is unused.
+    pub used_by: Option<String>,
+    /// Fund value deposited with the ticket so far.
+    pub redeemed_value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistsResponse {
+    pub whitelists: Vec<WhitelistResponse>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -73,9 +296,15 @@ pub struct ParticipantsCountResponse {
     pub count: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParticipantInfo {
+    pub address: String,
+    pub data: Participant,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GetParticipantsResponse {
-    pub participants: Vec<String>,
+    pub participants: Vec<ParticipantInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -85,8 +314,10 @@ pub struct GetParticipantResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GetSaleStatusResponse {
-    pub private_sold_amount: Uint128,
-    pub public_sold_amount: Uint128
+    pub sold_amount: Uint128,
+    pub raised_amounts: Vec<Asset>,
+    pub status: SaleStatus,
+    pub clearing_price: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -95,14 +326,85 @@ pub struct PresaleInfoResponse {
     pub owner: String,
     // Accuracy
     pub accuracy: Uint128,
-    // Exchange rate
-    pub exchange_rate: Uint128,
-    // Presale Period.
-    pub presale_period: u64,
-    // Public Presale Start Time.
-    pub public_start_time: u64,
-    // Private Presale Start Time.
-    pub private_start_time: u64,
+    // Accepted fund assets with exchange rates and decimals
+    pub accepted_assets: Vec<AcceptedAsset>,
+    // Decimals of the reward token
+    pub reward_decimals: u32,
+    // Presale Start Time.
+    pub start_time: u64,
+    // Presale End Time.
+    pub end_time: u64,
     // Accuracy
     pub total_rewards_amount: Uint128,
-}
\ No newline at end of file
+    // Soft cap
+    pub soft_cap: Uint128,
+    // Price tranches
+    pub tranches: Vec<Tranche>,
+    // Sale mode
+    pub sale_mode: SaleMode,
+    // Vesting contract
+    pub vesting: Option<String>,
+    // Claim start time without vesting
+    pub claim_start_time: Option<u64>,
+    // Launchpad treasury
+    pub treasury: String,
+    // Platform fee in basis points
+    pub platform_fee_bps: u64,
+    // Liquidity provisioning
+    pub liquidity: Option<LiquidityInfo>,
+    // Fund amounts provided as liquidity
+    pub provided_liquidity: Vec<Asset>,
+    // Staking tiers
+    pub staking_tiers: Option<StakingTiersInfo>,
+    // Participation requirement of locked tokens
+    pub lock_requirement: Option<LockRequirementInfo>,
+    // Cool-off period
+    pub cool_off: Option<CoolOff>,
+    // Whitelist merkle leaf encoding
+    pub whitelist_leaf_format: LeafFormat,
+    // Signer of the allocation tickets
+    pub kyc_signer: Option<KycSigner>,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundsResponse {
+    pub rounds: Vec<Round>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurrentTrancheResponse {
+    /// None when there is no tranche schedule or every tranche is sold.
+    pub tranche_id: Option<u64>,
+    pub price_multiplier: Decimal,
+    /// Reward token amount left at the current price.
+    pub remaining_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OversubscriptionResponse {
+    /// Reward token amount requested by all contributions.
+    pub requested_amount: Uint128,
+    pub total_rewards_amount: Uint128,
+    /// Requested amount over the total rewards.
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryResponse {
+    pub lottery: Option<Lottery>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryWinnerInfo {
+    pub address: String,
+    pub allocation: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryWinnersResponse {
+    pub winners: Vec<LotteryWinnerInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyLotteryResponse {
+    pub valid: bool,
+}
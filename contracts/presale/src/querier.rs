@@ -3,6 +3,12 @@ use cosmwasm_std::{
     StdResult, WasmQuery, Uint128,
 };
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use locking::msg::{LockInfoResponse, QueryMsg as LockingQueryMsg};
+use locking::state::LockInfo;
+use staking::msg::{QueryMsg as StakingQueryMsg, StakerInfoResponse};
+
+use crate::pair::{PairInfo, PoolResponse, QueryMsg as PairQueryMsg};
+use crate::types::FundAsset;
 
 pub fn query_all_balances(deps: Deps, account_addr: Addr) -> StdResult<Vec<Coin>> {
     // load price form the oracle
@@ -51,6 +57,52 @@ pub fn query_decimals(deps: Deps, contract_addr: String) -> StdResult<u32> {
     Ok(token_info.decimals.into())
 }
 
+pub fn query_fund_balance(deps: Deps, fund_asset: &FundAsset, account_addr: Addr) -> StdResult<Uint128> {
+    match fund_asset {
+        FundAsset::Native { denom } => query_balance(deps, account_addr, denom.clone()),
+        FundAsset::Cw20 { contract_addr } => query_token_balance(deps, Addr::unchecked(contract_addr), account_addr),
+    }
+}
+
+pub fn query_fund_decimals(deps: Deps, fund_asset: &FundAsset) -> StdResult<u32> {
+    match fund_asset {
+        // native stablecoins use 6 decimals
+        FundAsset::Native { .. } => Ok(6),
+        FundAsset::Cw20 { contract_addr } => query_decimals(deps, contract_addr.clone()),
+    }
+}
+
+pub fn query_pair_info(deps: Deps, pair_contract: String) -> StdResult<PairInfo> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract,
+        msg: to_binary(&PairQueryMsg::Pair {})?,
+    }))
+}
+
+pub fn query_pool(deps: Deps, pair_contract: String) -> StdResult<PoolResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract,
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))
+}
+
+pub fn query_bond_amount(deps: Deps, staking_contract: String, staker: String) -> StdResult<Uint128> {
+    let staker_info: StakerInfoResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: staking_contract,
+        msg: to_binary(&StakingQueryMsg::StakerInfo { staker, block_time: None })?,
+    }))?;
+
+    Ok(staker_info.bond_amount)
+}
+
+pub fn query_lock_info(deps: Deps, locking_contract: String, address: String) -> StdResult<LockInfo> {
+    let lock_info: LockInfoResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: locking_contract,
+        msg: to_binary(&LockingQueryMsg::LockInfo { address })?,
+    }))?;
+
+    Ok(lock_info.info)
+}
 
 pub fn query_supply(deps: Deps, contract_addr: Addr) -> StdResult<Uint128> {
     let token_info: TokenInfoResponse =
@@ -2,8 +2,10 @@ use cosmwasm_storage::{singleton, singleton_read};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Uint128, Storage, StdResult};
-use cw_storage_plus::{Map};
+use cosmwasm_std::{Binary, CanonicalAddr, Decimal, Order, Uint128, Storage, StdResult};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::types::{Asset, FundAsset, OrderBy};
 
 const KEY_STATE: &[u8] = b"state";
 
@@ -13,34 +15,330 @@ pub struct State {
     pub owner: CanonicalAddr,
 
     /************** Address Infos *************/
-    // Token for fundraise.
-    pub fund_denom: String,
+    // Tokens for fundraise.
+    pub accepted_assets: Vec<AcceptedAsset>,
     // Token for distribution.
     pub reward_token: CanonicalAddr,
-    // Vesting Contract.
-    pub vesting: CanonicalAddr,
-    // Whitelist Merkle Root.
-    pub whitelist_merkle_root: String,
+    // Decimals of the reward token.
+    pub reward_decimals: u32,
+    // Vesting Contract, none when the bought tokens are claimed directly from the presale.
+    pub vesting: Option<CanonicalAddr>,
+    // Launchpad treasury receiving the platform fee.
+    pub treasury: CanonicalAddr,
 
     /************** Presale Params *************/
-    // Fixed rate between fundToken vs rewardToken = reward / fund * ACCURACY.
-    pub exchange_rate: Uint128,
-    // Presale Period.
-    pub presale_period: u64,
-    // Public Presale Start Time.
-    pub public_start_time: u64,
-    // Private Presale Start Time.
-    pub private_start_time: u64,
+    // Sale rounds, in order. Round id is the index in this list.
+    pub rounds: Vec<Round>,
     // Total reward token amount
     pub total_rewards_amount: Uint128,
+    // Minimum reward token amount to be sold for the presale to succeed
+    pub soft_cap: Uint128,
+    // Price schedule by sold amount, empty for a flat price
+    pub tranches: Vec<Tranche>,
+    // Fixed price or dutch auction
+    pub sale_mode: SaleMode,
+    // Share of the raised funds paid to the treasury, in basis points
+    pub platform_fee_bps: u64,
+    // Bought tokens can be claimed from this time when there is no vesting contract
+    pub claim_start_time: Option<u64>,
+    // Liquidity provided on the DEX when the presale succeeds
+    pub liquidity: Option<LiquidityConfig>,
+    // Allocations of the staking rounds by bonded amount
+    pub staking_tiers: Option<StakingTiers>,
+    // Locked tokens required to participate
+    pub lock_requirement: Option<LockRequirement>,
+    // Winner draw allocating the lottery rounds
+    pub lottery: Option<Lottery>,
+    // Participants can withdraw their contributions during the cool-off period
+    pub cool_off: Option<CoolOff>,
+    // Encoding of the whitelist merkle leaves, plain for sales created before it could be chosen
+    #[serde(default)]
+    pub whitelist_leaf_format: LeafFormat,
+    // Off-chain KYC signer of the allocation tickets
+    pub kyc_signer: Option<KycSigner>,
 
     /************** Status Info *************/
-    // Reward token amount sold by private sale
-    pub private_sold_amount: Uint128,
-    // Reward token amount sold by public sale
-    pub public_sold_amount: Uint128,
-    // Participants address list
-    pub userlist: Vec<String>,
+    // Fund token amounts raised per accepted asset
+    pub raised_amounts: Vec<Asset>,
+    // Result of the presale, set by finalization
+    pub status: SaleStatus,
+    // Reward token amounts bought with each accepted asset
+    pub bought_amounts: Vec<Asset>,
+    // Uniform dutch auction price, set when the auction sells out or by finalization
+    pub clearing_price: Option<Decimal>,
+    // Whether the raised funds of a dutch auction or pro-rata sale have been withdrawn
+    pub funds_withdrawn: bool,
+    // Fund amounts paired with reward tokens on finalization
+    pub provided_liquidity: Vec<Asset>,
+    // Reward token amount claimed directly from the presale
+    pub claimed_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidityConfig {
+    // Astroport-style pair of the fund asset and the reward token.
+    pub pair: CanonicalAddr,
+    // LP token of the pair.
+    pub liquidity_token: CanonicalAddr,
+    // Fund asset paired with the reward token.
+    pub fund_asset: FundAsset,
+    // Share of the raised fund asset provided as liquidity, in basis points.
+    pub liquidity_bps: u64,
+    // Locking contract for the LP tokens, they go to the owner when not set.
+    pub locking: Option<CanonicalAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockRequirement {
+    // Locking contract queried for the locked amounts.
+    pub locking: CanonicalAddr,
+    // Minimum amount locked to participate.
+    pub min_amount: Uint128,
+    // Tokens have to be locked since this time, locking more resets the lock time.
+    pub snapshot_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CoolOff {
+    // Seconds after the first deposit into a round during which it can be withdrawn.
+    pub period: u64,
+    // Share of the withdrawn funds paid to the treasury, in basis points.
+    pub fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Lottery {
+    // Registration for the draw is open until this time.
+    pub registration_end_time: u64,
+    // The owner reveals the secret until this time, the lottery rounds are cancelled without it afterwards.
+    pub reveal_deadline: u64,
+    // Number of registrants drawn as winners.
+    pub winners_count: u64,
+    // Max fund value per winner.
+    pub allocation: Uint128,
+    // Number of registered accounts.
+    pub registrants_count: u64,
+    // Hex-encoded running sha256 hash of the registrations, mixed into the draw seed.
+    pub registrations_hash: String,
+    // Hex-encoded sha256 hash of the owner secret, committed when the presale is created.
+    pub commitment: String,
+    // Set when the owner reveals the secret after registration closes.
+    pub draw: Option<LotteryDraw>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryDraw {
+    // Revealed owner secret.
+    pub secret: String,
+    // Block height of the draw.
+    pub height: u64,
+    // Block time of the draw.
+    pub time: u64,
+    // Hex-encoded seed of the draw, hashed from the secret and the registrations hash.
+    pub seed: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingTiers {
+    // Staking contract queried for the bonded amounts.
+    pub staking: CanonicalAddr,
+    // Tiers by ascending minimum bonded amount.
+    pub tiers: Vec<StakingTier>,
+}
+
+impl StakingTiers {
+    /// Highest tier reached by the bonded amount.
+    pub fn tier(&self, bond_amount: Uint128) -> Option<&StakingTier> {
+        self.tiers.iter().rev().find(|tier| bond_amount >= tier.min_bond_amount)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingTier {
+    // Minimum amount bonded in the staking contract to reach the tier.
+    pub min_bond_amount: Uint128,
+    // Allocation granted to the tier.
+    pub allocation: TierAllocation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TierAllocation {
+    // Max fund value per participant
+    Amount(Uint128),
+    // Pool weight, multiplies the max allocation of the round
+    Weight(Decimal),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleMode {
+    // Tokens are sold at the round and tranche prices
+    FixedPrice,
+    // Price decays linearly from the start price to the floor price over the presale,
+    // every bid pays the uniform clearing price in the end
+    DutchAuction {
+        start_price_multiplier: Decimal,
+        floor_price_multiplier: Decimal,
+    },
+    // Contributions are accepted over the hard cap and every participant
+    // gets a pro-rata share of the total rewards in the end
+    ProRata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleStatus {
+    // Presale is not finalized yet
+    Pending,
+    // Soft cap reached, raised funds go to the owner
+    Succeeded,
+    // Soft cap not reached, participants can claim refunds
+    Failed,
+    // Cancelled by the owner, participants can claim refunds
+    Cancelled,
+}
+
+impl State {
+    // Reward token amount sold by all rounds
+    pub fn sold_amount(&self) -> Uint128 {
+        self.rounds.iter().map(|round| round.sold_amount).sum()
+    }
+
+    // Presale Start Time.
+    pub fn start_time(&self) -> u64 {
+        self.rounds.iter().map(|round| round.start_time).min().unwrap_or_default()
+    }
+
+    // Presale End Time.
+    pub fn end_time(&self) -> u64 {
+        self.rounds.iter().map(|round| round.end_time).max().unwrap_or_default()
+    }
+
+    // Dutch auction price at the given time, None for fixed price sales.
+    pub fn auction_price(&self, time: u64) -> Option<Decimal> {
+        match self.sale_mode {
+            SaleMode::FixedPrice | SaleMode::ProRata => None,
+            SaleMode::DutchAuction { start_price_multiplier, floor_price_multiplier } => {
+                let (start_time, end_time) = (self.start_time(), self.end_time());
+                let elapsed = time.clamp(start_time, end_time) - start_time;
+                let decay = (start_price_multiplier - floor_price_multiplier) * Decimal::from_ratio(elapsed, end_time - start_time);
+                Some(start_price_multiplier - decay)
+            }
+        }
+    }
+
+    // Requested reward token amount over the total rewards, below 1 when undersubscribed.
+    pub fn oversubscription_ratio(&self) -> Decimal {
+        if self.total_rewards_amount.is_zero() {
+            return Decimal::zero();
+        }
+        Decimal::from_ratio(self.sold_amount(), self.total_rewards_amount)
+    }
+
+    // Tranche index at the given sold amount and the reward token amount left at its price.
+    pub fn current_tranche(&self, sold_amount: Uint128) -> Option<(usize, Uint128)> {
+        let mut tranche_end = Uint128::zero();
+        for (index, tranche) in self.tranches.iter().enumerate() {
+            tranche_end += tranche.amount;
+            if sold_amount < tranche_end {
+                return Some((index, tranche_end - sold_amount));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Tranche {
+    // Reward token amount sold at this price.
+    pub amount: Uint128,
+    // Price relative to the exchange rates of the accepted assets, applied on top of the round price.
+    pub price_multiplier: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationType {
+    // Whitelisted users can buy up to their allocation, regardless of the round cap
+    Guaranteed,
+    // Whitelisted users can buy up to their allocation until the round cap is sold out
+    Fcfs,
+    // Anyone can buy up to the round max allocation until the round cap is sold out
+    Public,
+    // Stakers can buy up to the allocation of their staking tier, regardless of the round cap
+    Staking,
+    // Lottery winners can buy up to the lottery allocation, regardless of the round cap
+    Lottery,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeafFormat {
+    // sha256 of the sender and the allocation concatenated, ambiguous and only kept for the sales created with it
+    #[default]
+    Plain,
+    // sha256 of the domain tag, chain id, presale address, round id, sender and allocation, length-prefixed
+    V1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KycSigner {
+    // Signature scheme of the key.
+    pub key_type: KycKeyType,
+    // Public key, a 33-byte compressed or 65-byte uncompressed secp256k1 key or a 32-byte ed25519 key.
+    pub public_key: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KycKeyType {
+    Secp256k1,
+    Ed25519,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    // Round name.
+    pub name: String,
+    // Round Start Time.
+    pub start_time: u64,
+    // Round End Time.
+    pub end_time: u64,
+    // Round price relative to the exchange rates of the accepted assets, 1 for the base price.
+    pub price_multiplier: Decimal,
+    // Whitelist Merkle Root, empty to skip whitelist verification.
+    pub whitelist_merkle_root: String,
+    // Allocation rule of the round.
+    pub allocation_type: AllocationType,
+    // Max fund value per participant in public rounds, scaled by the tier weight in staking rounds.
+    pub max_allocation: Option<Uint128>,
+    // Max reward token amount sold by fcfs and public rounds.
+    pub cap: Option<Uint128>,
+    // Reward token amount sold by this round.
+    pub sold_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Whitelist {
+    // Hex-encoded merkle root of the list.
+    pub merkle_root: String,
+    // Guaranteed and fcfs rounds open to the list.
+    pub round_ids: Vec<u64>,
+    // Max reward token amount sold to the list, withdrawn contributions do not free it up.
+    pub cap: Option<Uint128>,
+    // Reward token amount sold to the list.
+    pub sold_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AcceptedAsset {
+    // Token accepted for fundraise.
+    pub info: FundAsset,
+    // Fixed rate between fundToken vs rewardToken = reward / fund * ACCURACY.
+    pub exchange_rate: Uint128,
+    // Decimals of the fund token.
+    pub decimals: u32,
 }
 
 pub fn store_state(storage: &mut dyn Storage, state: &State) -> StdResult<()> {
@@ -51,24 +349,160 @@ pub fn read_state(storage: &dyn Storage) -> StdResult<State> {
     singleton_read::<State>(storage, KEY_STATE).load()
 }
 
+// State of the presales deployed before versioning, selling in a private and a public phase.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct AlloInfo {
-    // Max allocation for this user in public presale
-    pub public_allocation: Uint128,
-    // Max allocation for this user in private presale
-    pub private_allocation: Uint128,
+pub struct LegacyState {
+    pub owner: CanonicalAddr,
+    pub fund_denom: String,
+    pub reward_token: CanonicalAddr,
+    pub vesting: CanonicalAddr,
+    pub whitelist_merkle_root: String,
+    pub exchange_rate: Uint128,
+    pub presale_period: u64,
+    pub public_start_time: u64,
+    pub private_start_time: u64,
+    pub total_rewards_amount: Uint128,
+    pub private_sold_amount: Uint128,
+    pub public_sold_amount: Uint128,
+    pub userlist: Vec<String>,
 }
 
-pub const PRIVATE_SOLD_FUNDS: Map<String, Uint128> = Map::new("private_sold_funds");
+pub fn read_legacy_state(storage: &dyn Storage) -> StdResult<LegacyState> {
+    singleton_read::<LegacyState>(storage, KEY_STATE).load()
+}
+
+// Participant of the presales deployed before versioning, funded in the native fund denom.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyParticipant {
+    pub fund_balance: Uint128,
+    pub reward_balance: Uint128,
+}
+
+pub const LEGACY_PARTICIPANTS: Map<String, LegacyParticipant> = Map::new("participants");
+
+// Fund amount deposited by each participant in the private phase, before versioning.
+pub const LEGACY_PRIVATE_SOLD_FUNDS: Map<String, Uint128> = Map::new("private_sold_funds");
+
+// Fund value deposited by each participant per round.
+pub const ROUND_SOLD_FUNDS: Map<(u64, String), Uint128> = Map::new("round_sold_funds");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct RoundContribution {
+    // Time of the first deposit into the round, the cool-off period runs from it.
+    pub first_deposit_time: u64,
+    // Fund token amounts deposited into the round per asset.
+    pub fund_amounts: Vec<Asset>,
+    // Fund values of the deposits per asset, normalized to FUND_VALUE_DECIMALS.
+    pub fund_values: Vec<Asset>,
+    // Reward token amounts bought in the round with each fund asset.
+    pub bought_amounts: Vec<Asset>,
+}
+
+// Contribution of each participant per round, kept when the presale has a cool-off period.
+pub const ROUND_CONTRIBUTIONS: Map<(u64, String), RoundContribution> = Map::new("round_contributions");
 
 pub const ACCURACY: u128 = 100000000u128;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub const PROVIDE_LIQUIDITY_REPLY_ID: u64 = 1u64;
+
+pub const FEE_DENOMINATOR: u64 = 10000u64;
+
+// Max deviation of the pool price from the sale price when providing liquidity, in basis points.
+pub const LIQUIDITY_SLIPPAGE_BPS: u64 = 100u64;
+
+// Fund amounts of different assets are normalized to these decimals to be checked against allocations.
+pub const FUND_VALUE_DECIMALS: u32 = 6;
+
+// Highest token decimals the owner can set.
+pub const MAX_DECIMALS: u32 = 18;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct Participant {
-    // Fund token amount by participant.
+    // Fund value by participant, normalized to FUND_VALUE_DECIMALS.
     pub fund_balance: Uint128,
+    // Fund token amounts by participant per asset.
+    pub fund_balances: Vec<Asset>,
     // Reward token amount need to be vested.
     pub reward_balance: Uint128,
+    // Reward token amounts bought with each fund asset.
+    pub bought_amounts: Vec<Asset>,
+    // Whether the dutch auction bid or pro-rata contribution has been settled.
+    pub settled: bool,
+    // Whether the reward tokens have been claimed from the presale.
+    pub claimed: bool,
 }
 
 pub const PARTICIPANTS: Map<String, Participant> = Map::new("participants");
+
+pub const PARTICIPANTS_COUNT: Item<u64> = Item::new("participants_count");
+
+// Lottery registrants by registration id.
+pub const REGISTRANTS: Map<u64, String> = Map::new("registrants");
+
+// Registration id of each lottery registrant.
+pub const REGISTRATIONS: Map<String, u64> = Map::new("registrations");
+
+// Fund value allocated to each lottery winner.
+pub const LOTTERY_WINNERS: Map<String, Uint128> = Map::new("lottery_winners");
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_participants(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<Vec<(String, Participant)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (start, end, order_by) = match order_by {
+        Some(OrderBy::Asc) => (start_after.map(Bound::exclusive), None, OrderBy::Asc),
+        _ => (None, start_after.map(Bound::exclusive), OrderBy::Desc),
+    };
+
+    PARTICIPANTS
+        .range(storage, start, end, order_by.into())
+        .take(limit)
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketRedemption {
+    // Account the ticket was granted to.
+    pub account: String,
+    // Fund value deposited with the ticket so far, up to its allocation.
+    pub redeemed_value: Uint128,
+}
+
+// Nonces of the redeemed allocation tickets.
+pub const USED_TICKET_NONCES: Map<u64, TicketRedemption> = Map::new("used_ticket_nonces");
+
+// Named whitelists by id.
+pub const WHITELISTS: Map<String, Whitelist> = Map::new("whitelists");
+
+pub fn read_whitelists(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Whitelist)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    WHITELISTS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn read_lottery_winners(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    LOTTERY_WINNERS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
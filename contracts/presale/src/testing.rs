@@ -1,29 +1,124 @@
-use crate::contract::{execute, instantiate};
+use crate::contract::{calc_fund_amount, calc_reward_amount, execute, instantiate, migrate, query, reply, verify_whitelist};
 use crate::error::ContractError;
-use crate::msg::{InstantiateMsg, ExecuteMsg};
-use crate::state::AlloInfo;
-use cosmwasm_std::testing::{mock_env, mock_info, mock_dependencies};
+use crate::msg::{AcceptedAssetInfo, AllocationTicket, TicketNonceResponse, Cw20HookMsg, FundDecimals, InstantiateMsg, ExecuteMsg, LiquidityInfo, LockRequirementInfo, LotteryInfo, LotteryResponse, LotteryWinnersResponse, VerifyLotteryResponse, WhitelistInfo, WhitelistResponse, WhitelistsResponse, MigrateMsg, StakingTiersInfo, QueryMsg, GetParticipantsResponse, ParticipantsCountResponse, GetParticipantResponse, PresaleInfoResponse, GetSaleStatusResponse, RoundInfo, RoundsResponse, CurrentTrancheResponse, OversubscriptionResponse};
+use crate::state::{read_state, AcceptedAsset, AllocationType, CoolOff, KycKeyType, KycSigner, LeafFormat, Round, SaleMode, SaleStatus, StakingTier, TierAllocation, Tranche, ACCURACY};
+use crate::types::{Asset, FundAsset, OrderBy};
+use crate::lottery::{draw_winners, lottery_commitment, lottery_seed, registrations_hash};
+use crate::merkle::{whitelist_leaf, LeafContext};
+use crate::ticket::ticket_message;
+use crate::mock_querier::mock_dependencies;
+use crate::pair::{Asset as PairAsset, AssetInfo, ExecuteMsg as PairExecuteMsg, PairInfo, PoolResponse};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MOCK_CONTRACT_ADDR};
+use cw_storage_plus::Map;
 use cosmwasm_std::{
-    Uint128, Coin, Timestamp,
+    attr, to_vec, from_binary, Api, CanonicalAddr, from_slice, Binary, Storage, to_binary, BankMsg, Decimal, CosmosMsg, Reply, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Coin, Timestamp, WasmMsg,
 };
+use cosmwasm_storage::to_length_prefixed;
+use locking::state::LockInfo;
+use cw2::{get_contract_version, set_contract_version};
+use versioning::MigrationError;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
 
+fn mock_round(whitelist_merkle_root: &str, start_time: u64, end_time: u64) -> RoundInfo {
+    RoundInfo {
+        name: "round".to_string(),
+        start_time,
+        end_time,
+        price_multiplier: Decimal::one(),
+        whitelist_merkle_root: whitelist_merkle_root.to_string(),
+        allocation_type: AllocationType::Guaranteed,
+        max_allocation: None,
+        cap: None,
+    }
+}
 
-#[test]
-fn test_initialize() {
-    let mut deps = mock_dependencies(&[]);
-    let init_msg = InstantiateMsg {
-        fund_denom: "uusd".to_string(),
+fn mock_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(1u128) }],
         reward_token: "reward_token".to_string(),
-        vesting: "vesting".to_string(),
-        whitelist_merkle_root: "root".to_string(),
+        vesting: Some("vesting".to_string()),
+        claim_start_time: None,
+        rounds: vec![mock_round("root", 0, 100)],
 
-        exchange_rate: Uint128::from(1u128),
-        private_start_time: 0,
-        public_start_time: 0,
-        presale_period: 100,
+        total_rewards_amount: Uint128::from(1000000u128),
+        soft_cap: Uint128::zero(),
+        tranches: vec![],
+        sale_mode: SaleMode::FixedPrice,
+        treasury: "treasury".to_string(),
+        platform_fee_bps: 0,
+        liquidity: None,
+        staking_tiers: None,
+        lock_requirement: None,
+        lottery: None,
+        cool_off: None,
+        whitelist_leaf_format: None,
+        whitelists: vec![],
+        kyc_signer: None
+    }
+}
+
+// State and participants layout of the presales deployed before versioning
+#[derive(Serialize)]
+struct BaselineState {
+    owner: CanonicalAddr,
+    fund_denom: String,
+    reward_token: CanonicalAddr,
+    vesting: CanonicalAddr,
+    whitelist_merkle_root: String,
+    exchange_rate: Uint128,
+    presale_period: u64,
+    public_start_time: u64,
+    private_start_time: u64,
+    total_rewards_amount: Uint128,
+    private_sold_amount: Uint128,
+    public_sold_amount: Uint128,
+    userlist: Vec<String>,
+}
 
-        total_rewards_amount: Uint128::from(1000000u128)
+#[derive(Serialize, Deserialize)]
+struct BaselineParticipant {
+    fund_balance: Uint128,
+    reward_balance: Uint128,
+}
+
+// Stores a baseline presale selling 1:1 privately from 100 and publicly from 200 to 300,
+// participants are given with their fund balance and the part of it deposited privately.
+fn mock_baseline_presale(storage: &mut dyn Storage, whitelist_merkle_root: &str, participants: &[(&str, u128, u128)]) {
+    let api = MockApi::default();
+    let private_sold_amount: u128 = participants.iter().map(|(_, _, private_fund)| private_fund).sum();
+    let sold_amount: u128 = participants.iter().map(|(_, fund, _)| fund).sum();
+    let state = BaselineState {
+        owner: api.addr_canonicalize("owner").unwrap(),
+        fund_denom: "uusd".to_string(),
+        reward_token: api.addr_canonicalize("reward_token").unwrap(),
+        vesting: api.addr_canonicalize("vesting").unwrap(),
+        whitelist_merkle_root: whitelist_merkle_root.to_string(),
+        exchange_rate: Uint128::from(ACCURACY),
+        presale_period: 100,
+        public_start_time: 200,
+        private_start_time: 100,
+        total_rewards_amount: Uint128::from(1000u128),
+        private_sold_amount: Uint128::from(private_sold_amount),
+        public_sold_amount: Uint128::from(sold_amount - private_sold_amount),
+        userlist: participants.iter().map(|(user, _, _)| user.to_string()).collect(),
     };
+    storage.set(&to_length_prefixed(b"state"), &to_vec(&state).unwrap());
+
+    let baseline_participants: Map<String, BaselineParticipant> = Map::new("participants");
+    let private_sold_funds: Map<String, Uint128> = Map::new("private_sold_funds");
+    for (user, fund, private_fund) in participants.iter() {
+        let participant = BaselineParticipant { fund_balance: Uint128::from(*fund), reward_balance: Uint128::from(*fund) };
+        baseline_participants.save(storage, user.to_string(), &participant).unwrap();
+        private_sold_funds.save(storage, user.to_string(), &Uint128::from(*private_fund)).unwrap();
+    }
+}
+
+#[test]
+fn test_initialize() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = mock_instantiate_msg();
     let info = mock_info(&"owner".to_string(), &[]);
     let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
 
@@ -33,26 +128,15 @@ fn test_initialize() {
 #[test]
 fn test_security() {
     let mut deps = mock_dependencies(&[]);
-    let init_msg = InstantiateMsg {
-        fund_denom: "uusd".to_string(),
-        reward_token: "reward_token".to_string(),
-        vesting: "vesting".to_string(),
-        whitelist_merkle_root: "root".to_string(),
-
-        exchange_rate: Uint128::from(1u128),
-        private_start_time: 0,
-        public_start_time: 0,
-        presale_period: 100,
-
-        total_rewards_amount: Uint128::from(1000000u128)
-    };
+    let init_msg = mock_instantiate_msg();
     let info = mock_info(&"owner".to_string(), &[]);
     let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
-    let update_msg = ExecuteMsg::UpdatePresaleInfo {
-        new_private_start_time: 1,
-        new_public_start_time: 10,
-        new_presale_period: 100,
+    let now = mock_env().block.time.seconds();
+    let update_msg = ExecuteMsg::UpdateRound {
+        round_id: 0,
+        new_start_time: now + 1,
+        new_end_time: now + 100,
     };
     let transfer_ownership_msg = ExecuteMsg::TransferOwnerShip { new_owner: "user".to_string() };
 
@@ -86,21 +170,15 @@ fn test_security() {
 fn test_deposit() {
     let mut deps = mock_dependencies(&[]);
     let init_msg = InstantiateMsg {
-        fund_denom: "uusd".to_string(),
-        reward_token: "reward_token".to_string(),
-        vesting: "vesting".to_string(),
-        whitelist_merkle_root: "2c0540dec9298f8a56e5a017a1a2613b06f6f99fb89f2957430dfcbf8bf8ed9e".to_string(),
-
-        exchange_rate: Uint128::from(1u128),
-        private_start_time: 0,
-        public_start_time: 0,
-        presale_period: 1000,
-        total_rewards_amount: Uint128::from(1000000u128)
+        rounds: vec![mock_round("fd1d78df323fcd235dc128ae2985b7572fdeff994b0876ff50425df159ea60f6", 0, 1000)],
+        // the tree was built with plain leaves
+        whitelist_leaf_format: Some(LeafFormat::Plain),
+        ..mock_instantiate_msg()
     };
     let info = mock_info(&"owner".to_string(), &[]);
     let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
-    let allo_info = AlloInfo { public_allocation: Uint128::from(1000u128), private_allocation: Uint128::from(100u128) };
+    let allocation = Uint128::from(1000u128);
     let proof = vec![
         "752a380bf2251efcea11d8e71fa03418ba31ba34d72854139f45d56c7602ebc3".to_string(),
         "f3b499bf4aa1f3832d7661832ba8be436b6e5e7f9feb3c4b3a5d87bb8612e2cf".to_string()
@@ -114,6 +192,1829 @@ fn test_deposit() {
     ]);
     let mut env = mock_env();
     env.block.time = Timestamp::from_seconds(1);
-    let msg = ExecuteMsg::Deposit { allo_info, proof };
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof, whitelist_id: None, ticket: None };
     execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 }
+
+#[test]
+fn test_soft_cap_refund() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 1000)],
+        soft_cap: Uint128::from(500000u128),
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    let allocation = Uint128::from(1000u128);
+    let deposit_info = mock_info("user", &[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128)
+        }
+    ]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), deposit_info, msg).unwrap();
+
+    // refunds are not available before finalization
+    let user_info = mock_info("user", &[]);
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), ExecuteMsg::ClaimRefund {});
+    assert_eq!(res, Err(ContractError::RefundNotAvailable {}));
+
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), ExecuteMsg::Finalize {});
+    assert_eq!(res, Err(ContractError::StillInProgress {}));
+
+    env.block.time = Timestamp::from_seconds(1001);
+    execute(deps.as_mut(), env.clone(), user_info.clone(), ExecuteMsg::Finalize {}).unwrap();
+
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.status, SaleStatus::Failed);
+
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), ExecuteMsg::Finalize {});
+    assert_eq!(res, Err(ContractError::AlreadyFinalized {}));
+
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() });
+    assert_eq!(res, Err(ContractError::SaleFailed {}));
+
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), ExecuteMsg::ClaimRefund {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vesting".to_string(),
+                msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                    recp: "user".to_string(),
+                    amount: 0,
+                }).unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    let res = execute(deps.as_mut(), env, user_info, ExecuteMsg::ClaimRefund {});
+    assert_eq!(res, Err(ContractError::NothingToRefund {}));
+}
+
+#[test]
+fn test_soft_cap_reached() {
+    let mut deps = mock_dependencies(&[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500000u128)
+        }
+    ]);
+    deps.querier.with_token_balances(&[("reward_token", &[("vesting", Uint128::from(1000000u128))])]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 1000)],
+        soft_cap: Uint128::from(500000u128),
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    let allocation = Uint128::from(500000u128);
+    let deposit_info = mock_info("user", &[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500000u128)
+        }
+    ]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), deposit_info, msg).unwrap();
+
+    env.block.time = Timestamp::from_seconds(1001);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() });
+    assert_eq!(res, Err(ContractError::NotFinalized {}));
+
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::ClaimRefund {});
+    assert_eq!(res, Err(ContractError::RefundNotAvailable {}));
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() }).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "owner".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(500000u128),
+            }],
+        }))]
+    );
+
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::WithdrawUnsoldToken { receiver: "owner".to_string() }).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "vesting".to_string(),
+                recipient: "owner".to_string(),
+                amount: Uint128::from(500000u128),
+            }).unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_hard_cap() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(2 * ACCURACY) }],
+        rounds: vec![mock_round("", 0, 500), mock_round("", 501, 1000)],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let allocation = Uint128::from(10000u128);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1);
+
+    // 1200 uusd buys 600 reward tokens in the first round
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1200u128) }]), msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    // only 400 reward tokens are left for the second round, so 800 of 1000 uusd are accepted
+    env.block.time = Timestamp::from_seconds(600);
+    let msg = ExecuteMsg::Deposit { round_id: 1, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000u128) }]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vesting".to_string(),
+                msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                    recp: "user2".to_string(),
+                    amount: 400,
+                }).unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user2".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(200u128),
+                }],
+            })),
+        ]
+    );
+
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.sold_amount, Uint128::from(1000u128));
+
+    let msg = ExecuteMsg::Deposit { round_id: 1, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env, mock_info("user3", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg);
+    assert_eq!(res, Err(ContractError::HardCapReached {}));
+}
+
+#[test]
+fn test_cw20_fund_deposit() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_decimals("fund_token", 18);
+    deps.querier.with_token_balances(&[("fund_token", &[(MOCK_CONTRACT_ADDR, Uint128::from(2000000000000000000u128))])]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Cw20 { contract_addr: "fund_token".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 1000)],
+        total_rewards_amount: Uint128::from(1000000000u128),
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    let allocation = Uint128::from(10000000000000000000u128);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1);
+
+    // native deposits are rejected
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg);
+    assert_eq!(res, Err(ContractError::Funds {}));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user".to_string(),
+        amount: Uint128::from(2000000000000000000u128),
+        msg: to_binary(&Cw20HookMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None }).unwrap(),
+    });
+
+    // only the fund token can deliver deposits
+    let res = execute(deps.as_mut(), env.clone(), mock_info("other_token", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // 2 fund tokens with 18 decimals buy 2 reward tokens with 6 decimals
+    let res = execute(deps.as_mut(), env.clone(), mock_info("fund_token", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: "user".to_string(),
+                amount: 2000000,
+            }).unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    env.block.time = Timestamp::from_seconds(1001);
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Finalize {}).unwrap();
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() }).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "fund_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "owner".to_string(),
+                amount: Uint128::from(2000000000000000000u128),
+            }).unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_multiple_fund_assets() {
+    let mut deps = mock_dependencies(&[
+        Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) },
+        Coin { denom: "ibc/usdc".to_string(), amount: Uint128::from(200u128) },
+    ]);
+    let uusd = FundAsset::Native { denom: "uusd".to_string() };
+    let usdc = FundAsset::Native { denom: "ibc/usdc".to_string() };
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![
+            AcceptedAssetInfo { info: uusd.clone(), exchange_rate: Uint128::from(ACCURACY) },
+            AcceptedAssetInfo { info: usdc.clone(), exchange_rate: Uint128::from(2 * ACCURACY) },
+        ],
+        rounds: vec![mock_round("", 0, 1000)],
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    let allocation = Uint128::from(300u128);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1);
+
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uluna".to_string(), amount: Uint128::from(100u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::Funds {}));
+
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg.clone()).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "ibc/usdc".to_string(), amount: Uint128::from(200u128) }]), msg.clone()).unwrap();
+
+    // allocation is shared between all assets
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1u128) }]), msg);
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.fund_balance, Uint128::from(300u128));
+    assert_eq!(participant.data.reward_balance, Uint128::from(200u128));
+    assert_eq!(
+        participant.data.fund_balances,
+        vec![
+            Asset { info: uusd.clone(), amount: Uint128::from(100u128) },
+            Asset { info: usdc.clone(), amount: Uint128::from(200u128) },
+        ]
+    );
+
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.sold_amount, Uint128::from(200u128));
+    assert_eq!(status.raised_amounts, participant.data.fund_balances);
+
+    env.block.time = Timestamp::from_seconds(1001);
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Finalize {}).unwrap();
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() }).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: vec![Coin { denom: "ibc/usdc".to_string(), amount: Uint128::from(200u128) }],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_rounds() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![
+            RoundInfo {
+                name: "guaranteed".to_string(),
+                price_multiplier: Decimal::percent(50),
+                ..mock_round("fd1d78df323fcd235dc128ae2985b7572fdeff994b0876ff50425df159ea60f6", 0, 99)
+            },
+            RoundInfo {
+                name: "fcfs".to_string(),
+                allocation_type: AllocationType::Fcfs,
+                cap: Some(Uint128::from(150u128)),
+                ..mock_round("", 100, 199)
+            },
+            RoundInfo {
+                name: "public".to_string(),
+                allocation_type: AllocationType::Public,
+                max_allocation: Some(Uint128::from(50u128)),
+                ..mock_round("", 200, 300)
+            },
+        ],
+        whitelist_leaf_format: Some(LeafFormat::Plain),
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let proof = vec![
+        "752a380bf2251efcea11d8e71fa03418ba31ba34d72854139f45d56c7602ebc3".to_string(),
+        "f3b499bf4aa1f3832d7661832ba8be436b6e5e7f9feb3c4b3a5d87bb8612e2cf".to_string()
+    ];
+    let uusd = |amount: u128| vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }];
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+
+    let msg = ExecuteMsg::Deposit { round_id: 1, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(100)), msg);
+    assert_eq!(res, Err(ContractError::RoundNotInProgress {}));
+
+    let msg = ExecuteMsg::Deposit { round_id: 3, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(100)), msg);
+    assert_eq!(res, Err(ContractError::RoundNotFound {}));
+
+    // guaranteed round needs a whitelist proof and sells at half price
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(2000u128), proof: proof.clone(), whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &uusd(100)), msg);
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(1000u128), proof, whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &uusd(100)), msg).unwrap();
+
+    // fcfs round is limited by the round cap
+    env.block.time = Timestamp::from_seconds(150);
+    let msg = ExecuteMsg::Deposit { round_id: 1, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(100)), msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &uusd(100)), msg.clone()).unwrap();
+    assert_eq!(res.messages[1], SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user2".to_string(),
+        amount: uusd(50),
+    })));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user3", &uusd(100)), msg);
+    assert_eq!(res, Err(ContractError::HardCapReached {}));
+
+    // public round is open to everyone up to the max allocation
+    env.block.time = Timestamp::from_seconds(250);
+    let msg = ExecuteMsg::Deposit { round_id: 2, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user3", &uusd(60)), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+    execute(deps.as_mut(), env.clone(), mock_info("user3", &uusd(50)), msg).unwrap();
+
+    let rounds: RoundsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Rounds {}).unwrap()).unwrap();
+    assert_eq!(
+        rounds.rounds.iter().map(|round| round.sold_amount).collect::<Vec<Uint128>>(),
+        vec![Uint128::from(200u128), Uint128::from(150u128), Uint128::from(50u128)]
+    );
+
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.sold_amount, Uint128::from(400u128));
+}
+
+#[test]
+fn test_tranches() {
+    let mut deps = mock_dependencies(&[]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 100)],
+        total_rewards_amount: Uint128::from(300u128),
+        tranches: vec![
+            Tranche { amount: Uint128::from(100u128), price_multiplier: Decimal::one() },
+            Tranche { amount: Uint128::from(100u128), price_multiplier: Decimal::percent(200) },
+        ],
+        ..mock_instantiate_msg()
+    };
+
+    // tranches have to cover the whole sale
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.tranches[1].amount = Uint128::from(200u128);
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let uusd = |amount: u128| vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }];
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+
+    // deposit within the first tranche
+    execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(60)), msg.clone()).unwrap();
+    let tranche: CurrentTrancheResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::CurrentTranche {}).unwrap()).unwrap();
+    assert_eq!(tranche, CurrentTrancheResponse {
+        tranche_id: Some(0),
+        price_multiplier: Decimal::one(),
+        remaining_amount: Uint128::from(40u128),
+    });
+
+    // 40 tokens at the first price, the rest at double price
+    execute(deps.as_mut(), env.clone(), mock_info("user2", &uusd(100)), msg.clone()).unwrap();
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user2".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.reward_balance, Uint128::from(70u128));
+    let tranche: CurrentTrancheResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::CurrentTranche {}).unwrap()).unwrap();
+    assert_eq!(tranche, CurrentTrancheResponse {
+        tranche_id: Some(1),
+        price_multiplier: Decimal::percent(200),
+        remaining_amount: Uint128::from(170u128),
+    });
+
+    // the last tranche sells out and the rest is refunded
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user3", &uusd(400)), msg).unwrap();
+    assert_eq!(res.messages[1], SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user3".to_string(),
+        amount: uusd(60),
+    })));
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user3".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.reward_balance, Uint128::from(170u128));
+
+    let tranche: CurrentTrancheResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::CurrentTranche {}).unwrap()).unwrap();
+    assert_eq!(tranche.tranche_id, None);
+    assert_eq!(tranche.remaining_amount, Uint128::zero());
+}
+
+#[test]
+fn test_dutch_auction() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(300u128),
+        sale_mode: SaleMode::DutchAuction {
+            start_price_multiplier: Decimal::percent(200),
+            floor_price_multiplier: Decimal::one(),
+        },
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let uusd = |amount: u128| vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }];
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let mut env = mock_env();
+
+    // bids are not vested before clearing
+    env.block.time = Timestamp::from_seconds(0);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(200)), msg.clone()).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    env.block.time = Timestamp::from_seconds(50);
+    execute(deps.as_mut(), env.clone(), mock_info("user2", &uusd(150)), msg.clone()).unwrap();
+
+    // the auction sells out at 1.2
+    env.block.time = Timestamp::from_seconds(80);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user3", &uusd(240)), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user3".to_string(),
+        amount: uusd(120),
+    }))]);
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::SettleBid {});
+    assert_eq!(res, Err(ContractError::NotFinalized {}));
+
+    env.block.time = Timestamp::from_seconds(101);
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.clearing_price, Some(Decimal::percent(120)));
+
+    // user1 bought 100 tokens at 2.0 and gets back the difference to 1.2
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::SettleBid {}).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: uusd(80),
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: "user1".to_string(),
+                amount: 100,
+            }).unwrap(),
+            funds: vec![],
+        })),
+    ]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::SettleBid {});
+    assert_eq!(res, Err(ContractError::AlreadySettled {}));
+
+    // user3 paid the clearing price already
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user3", &[]), ExecuteMsg::SettleBid {}).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user4", &[]), ExecuteMsg::SettleBid {});
+    assert_eq!(res, Err(ContractError::NothingToSettle {}));
+
+    // only the funds at the clearing price are withdrawn
+    let msg = ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "owner".to_string(),
+        amount: uusd(360),
+    }))]);
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::AlreadyWithdrawn {}));
+}
+
+#[test]
+fn test_pro_rata() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(100u128),
+        sale_mode: SaleMode::ProRata,
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+    deps.querier.with_token_balances(&[("reward_token", &[("vesting", Uint128::from(100u128))])]);
+
+    let uusd = |amount: u128| vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }];
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+
+    // contributions over the hard cap are accepted
+    execute(deps.as_mut(), env.clone(), mock_info("user1", &uusd(150)), msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &uusd(50)), msg).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let oversubscription: OversubscriptionResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Oversubscription {}).unwrap()).unwrap();
+    assert_eq!(oversubscription, OversubscriptionResponse {
+        requested_amount: Uint128::from(200u128),
+        total_rewards_amount: Uint128::from(100u128),
+        ratio: Decimal::percent(200),
+    });
+
+    env.block.time = Timestamp::from_seconds(101);
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+
+    // half of each contribution is filled
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::SettleBid {}).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: uusd(75),
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: "user1".to_string(),
+                amount: 75,
+            }).unwrap(),
+            funds: vec![],
+        })),
+    ]);
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user1".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.reward_balance, Uint128::from(75u128));
+    assert_eq!(participant.data.fund_balance, Uint128::from(75u128));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &[]), ExecuteMsg::SettleBid {}).unwrap();
+    assert_eq!(res.messages[0], SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user2".to_string(),
+        amount: uusd(25),
+    })));
+
+    let msg = ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "owner".to_string(),
+        amount: uusd(100),
+    }))]);
+
+    // everything was sold
+    let msg = ExecuteMsg::WithdrawUnsoldToken { receiver: "owner".to_string() };
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages[0], SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "reward_token".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: "vesting".to_string(),
+            recipient: "owner".to_string(),
+            amount: Uint128::zero(),
+        }).unwrap(),
+        funds: vec![],
+    })));
+}
+
+#[test]
+fn test_round_windows() {
+    let mut deps = mock_dependencies(&[]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 100), mock_round("", 100, 200)],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+
+    // rounds must not overlap
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    // rounds must be in order
+    init_msg.rounds = vec![mock_round("", 101, 200), mock_round("", 0, 100)];
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    let now = mock_env().block.time.seconds();
+    init_msg.rounds = vec![mock_round("", now + 100, now + 200), mock_round("", now + 201, now + 300)];
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    // the first round can't be extended into the second one
+    let msg = ExecuteMsg::UpdateRound { round_id: 0, new_start_time: now + 100, new_end_time: now + 250 };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    // the first round is closed while the second one is open
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(now + 250);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env, mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg);
+    assert_eq!(res, Err(ContractError::RoundNotInProgress {}));
+}
+
+#[test]
+fn test_platform_fee() {
+    let mut deps = mock_dependencies(&[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000u128) }]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 100)],
+        total_rewards_amount: Uint128::from(1000u128),
+        platform_fee_bps: 10001,
+        ..mock_instantiate_msg()
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.platform_fee_bps = 250;
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let presale_info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(presale_info.treasury, "treasury".to_string());
+    assert_eq!(presale_info.platform_fee_bps, 250);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000u128) }]), msg).unwrap();
+
+    env.block.time = Timestamp::from_seconds(101);
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+
+    // 2.5% of the raised funds go to the treasury
+    let msg = ExecuteMsg::WithdrawFunds { receiver: "project".to_string() };
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "project".to_string(),
+            amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(975u128) }],
+        })),
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(25u128) }],
+        })),
+    ]);
+    assert_eq!(res.attributes[1], attr("project_amount", "975uusd"));
+    assert_eq!(res.attributes[2], attr("fee_amount", "25uusd"));
+}
+
+#[test]
+fn test_liquidity() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_pair(PairInfo {
+        asset_infos: vec![
+            AssetInfo::NativeToken { denom: "uusd".to_string() },
+            AssetInfo::Token { contract_addr: "reward_token".to_string() },
+        ],
+        contract_addr: "pair".to_string(),
+        liquidity_token: "lp_token".to_string(),
+    });
+    deps.querier.with_token_balances(&[
+        ("reward_token", &[("vesting", Uint128::from(500u128))]),
+        ("lp_token", &[(MOCK_CONTRACT_ADDR, Uint128::from(70u128))]),
+    ]);
+    // the pool was seeded within the slippage tolerance of the sale price
+    deps.querier.with_pool("pair", PoolResponse {
+        assets: vec![
+            PairAsset { info: AssetInfo::NativeToken { denom: "uusd".to_string() }, amount: Uint128::from(1005u128) },
+            PairAsset { info: AssetInfo::Token { contract_addr: "reward_token".to_string() }, amount: Uint128::from(1000u128) },
+        ],
+        total_share: Uint128::from(1000u128),
+    });
+
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 100)],
+        total_rewards_amount: Uint128::from(1000u128),
+        liquidity: Some(LiquidityInfo {
+            pair: "pair".to_string(),
+            fund_asset: FundAsset::Native { denom: "ujuno".to_string() },
+            liquidity_bps: 5000,
+            locking: Some("locking".to_string()),
+        }),
+        ..mock_instantiate_msg()
+    };
+
+    // the paired asset has to be accepted
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.liquidity.as_mut().unwrap().fund_asset = FundAsset::Native { denom: "uusd".to_string() };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(400u128) }]), msg).unwrap();
+
+    // half of the raised funds would need 200 reward tokens, but only 100 are unsold in vesting
+    env.block.time = Timestamp::from_seconds(101);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+    assert!(res.attributes.contains(&attr("liquidity", "provided")));
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "vesting".to_string(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(100u128),
+            }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: "pair".to_string(),
+                amount: Uint128::from(100u128),
+                expires: None,
+            }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::reply_on_success(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair".to_string(),
+            msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                assets: vec![
+                    PairAsset { info: AssetInfo::NativeToken { denom: "uusd".to_string() }, amount: Uint128::from(100u128) },
+                    PairAsset { info: AssetInfo::Token { contract_addr: "reward_token".to_string() }, amount: Uint128::from(100u128) },
+                ],
+                slippage_tolerance: Some(Decimal::percent(1)),
+                auto_stake: None,
+                receiver: None,
+            }).unwrap(),
+            funds: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }],
+        }), 1),
+    ]);
+
+    let presale_info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(presale_info.provided_liquidity, vec![Asset { info: FundAsset::Native { denom: "uusd".to_string() }, amount: Uint128::from(100u128) }]);
+
+    // received LP tokens are locked
+    let msg = Reply { id: 1, result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }) };
+    let res = reply(deps.as_mut(), env, msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "lp_token".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: "locking".to_string(),
+            amount: Uint128::from(70u128),
+            msg: to_binary(&locking::msg::Cw20HookMsg::Deposit {}).unwrap(),
+        }).unwrap(),
+        funds: vec![],
+    }))]);
+
+    // a pool seeded away from the sale price is not paired, the sale still finalizes
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_pair(PairInfo {
+        asset_infos: vec![
+            AssetInfo::NativeToken { denom: "uusd".to_string() },
+            AssetInfo::Token { contract_addr: "reward_token".to_string() },
+        ],
+        contract_addr: "pair".to_string(),
+        liquidity_token: "lp_token".to_string(),
+    });
+    deps.querier.with_token_balances(&[("reward_token", &[("vesting", Uint128::from(500u128))])]);
+    deps.querier.with_pool("pair", PoolResponse {
+        assets: vec![
+            PairAsset { info: AssetInfo::NativeToken { denom: "uusd".to_string() }, amount: Uint128::from(10u128) },
+            PairAsset { info: AssetInfo::Token { contract_addr: "reward_token".to_string() }, amount: Uint128::from(1000u128) },
+        ],
+        total_share: Uint128::from(100u128),
+    });
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(1000u128), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(400u128) }]), msg).unwrap();
+
+    env.block.time = Timestamp::from_seconds(101);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+    assert!(res.attributes.contains(&attr("succeeded", "true")));
+    assert!(res.attributes.contains(&attr("liquidity", "pool_price_mismatch")));
+    assert_eq!(res.messages, vec![]);
+
+    let presale_info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(presale_info.provided_liquidity, vec![]);
+}
+
+#[test]
+fn test_participants() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    for user in ["user1", "user2", "user3", "user1"] {
+        execute(deps.as_mut(), env.clone(), mock_info(user, &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg.clone()).unwrap();
+    }
+
+    let count: ParticipantsCountResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ParticipantsCount {}).unwrap()).unwrap();
+    assert_eq!(count.count, 3);
+
+    let msg = QueryMsg::GetParticipants { start_after: None, limit: Some(2), order_by: Some(OrderBy::Asc) };
+    let res: GetParticipantsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.participants.iter().map(|p| p.address.as_str()).collect::<Vec<&str>>(), vec!["user1", "user2"]);
+    assert_eq!(res.participants[0].data.fund_balance, Uint128::from(20u128));
+
+    let msg = QueryMsg::GetParticipants { start_after: Some("user2".to_string()), limit: Some(2), order_by: Some(OrderBy::Asc) };
+    let res: GetParticipantsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.participants.iter().map(|p| p.address.as_str()).collect::<Vec<&str>>(), vec!["user3"]);
+
+    // pages past the end are empty
+    let msg = QueryMsg::GetParticipants { start_after: Some("user3".to_string()), limit: None, order_by: Some(OrderBy::Asc) };
+    let res: GetParticipantsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert!(res.participants.is_empty());
+
+    let msg = QueryMsg::GetParticipants { start_after: Some("user3".to_string()), limit: None, order_by: None };
+    let res: GetParticipantsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.participants.iter().map(|p| p.address.as_str()).collect::<Vec<&str>>(), vec!["user2", "user1"]);
+
+    // earlier versions kept the participants list in the state, the migrated participants keep their balances
+    let mut deps = mock_dependencies(&[]);
+    mock_baseline_presale(&mut deps.storage, "", &[("user1", 20, 0), ("user2", 30, 10), ("user3", 40, 40), ("user4", 50, 0)]);
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+    let count: ParticipantsCountResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ParticipantsCount {}).unwrap()).unwrap();
+    assert_eq!(count.count, 4);
+    let msg = QueryMsg::GetParticipants { start_after: Some("user2".to_string()), limit: None, order_by: Some(OrderBy::Asc) };
+    let res: GetParticipantsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.participants.iter().map(|p| p.address.as_str()).collect::<Vec<&str>>(), vec!["user3", "user4"]);
+    let uusd = FundAsset::Native { denom: "uusd".to_string() };
+    assert_eq!(res.participants[1].data.fund_balance, Uint128::from(50u128));
+    assert_eq!(res.participants[1].data.fund_balances, vec![Asset { info: uusd.clone(), amount: Uint128::from(50u128) }]);
+    assert_eq!(res.participants[1].data.reward_balance, Uint128::from(50u128));
+    assert_eq!(res.participants[1].data.bought_amounts, vec![Asset { info: uusd, amount: Uint128::from(50u128) }]);
+}
+
+#[test]
+fn test_migrate_version() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    // the participants list is only moved when coming from an older version
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes, vec![
+        attr("previous_version", env!("CARGO_PKG_VERSION")),
+        attr("new_version", env!("CARGO_PKG_VERSION")),
+    ]);
+
+    set_contract_version(&mut deps.storage, "crates.io:presale", "99.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::CannotDowngrade {}));
+
+    set_contract_version(&mut deps.storage, "crates.io:vesting", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::InvalidContract {}));
+
+    // presales deployed before versioning sold in a private and a public phase,
+    // their whitelist leaves hashed both phase allocations and are only dropped once deposits are over
+    let mut deps = mock_dependencies(&[]);
+    mock_baseline_presale(&mut deps.storage, "b1e5f5709783df6791e6327458961c81ac685cc89e87803e4197d91a964254ee", &[("user1", 150, 100)]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(300);
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::LegacyWhitelist {});
+    env.block.time = Timestamp::from_seconds(301);
+    migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+    let state = read_state(&deps.storage).unwrap();
+    assert_eq!(state.rounds[0].whitelist_merkle_root, "");
+    assert_eq!(state.whitelist_leaf_format, LeafFormat::V1);
+
+    let mut deps = mock_dependencies(&[]);
+    mock_baseline_presale(&mut deps.storage, "", &[("user1", 150, 100), ("user2", 50, 50)]);
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes, vec![
+        attr("previous_version", "none"),
+        attr("new_version", env!("CARGO_PKG_VERSION")),
+        attr("migrated_participants", "2"),
+    ]);
+
+    let uusd = FundAsset::Native { denom: "uusd".to_string() };
+    let state = read_state(&deps.storage).unwrap();
+    assert_eq!(state.accepted_assets, vec![AcceptedAsset { info: uusd.clone(), exchange_rate: Uint128::from(ACCURACY), decimals: 6 }]);
+    assert_eq!(state.vesting, Some(deps.api.addr_canonicalize("vesting").unwrap()));
+    assert_eq!(state.rounds, vec![Round {
+        name: "presale".to_string(),
+        start_time: 100,
+        end_time: 300,
+        price_multiplier: Decimal::one(),
+        whitelist_merkle_root: "".to_string(),
+        allocation_type: AllocationType::Guaranteed,
+        max_allocation: None,
+        cap: None,
+        sold_amount: Uint128::from(200u128),
+    }]);
+    assert_eq!(state.raised_amounts, vec![Asset { info: uusd.clone(), amount: Uint128::from(200u128) }]);
+    assert_eq!(state.bought_amounts, vec![Asset { info: uusd, amount: Uint128::from(200u128) }]);
+    assert_eq!(get_contract_version(&deps.storage).unwrap().contract, "crates.io:presale");
+
+    // the private phase funds count against the allocation in the round
+    assert!(!Map::<String, Uint128>::new("private_sold_funds").has(&deps.storage, "user1".to_string()));
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(250);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(160u128), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(11u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+    execute(deps.as_mut(), env, mock_info("user1", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg).unwrap();
+}
+
+#[test]
+fn test_staking_tiers() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_bond_amounts(&[("staking", &[
+        ("user1", Uint128::from(50u128)),
+        ("user2", Uint128::from(500u128)),
+        ("user3", Uint128::from(1000u128)),
+    ])]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Staking, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+
+    // staking rounds need the tiers
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    // weighted tiers need the round max allocation
+    init_msg.staking_tiers = Some(StakingTiersInfo {
+        staking: "staking".to_string(),
+        tiers: vec![
+            StakingTier { min_bond_amount: Uint128::from(100u128), allocation: TierAllocation::Amount(Uint128::from(50u128)) },
+            StakingTier { min_bond_amount: Uint128::from(1000u128), allocation: TierAllocation::Weight(Decimal::percent(200)) },
+        ],
+    });
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.rounds[0].max_allocation = Some(Uint128::from(100u128));
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone()).unwrap();
+    let presale_info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(presale_info.staking_tiers, init_msg.staking_tiers);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    // allocation and proof are not needed
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+
+    // below the lowest tier
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(60u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+    execute(deps.as_mut(), env.clone(), mock_info("user2", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(50u128) }]), msg.clone()).unwrap();
+
+    // twice the round max allocation
+    execute(deps.as_mut(), env.clone(), mock_info("user3", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(200u128) }]), msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), env, mock_info("user3", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1u128) }]), msg);
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+}
+
+#[test]
+fn test_lock_requirement() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_lock_infos(&[("locking", &[
+        ("user1", LockInfo { amount: Uint128::from(50u128), last_locked_time: 10, last_unlocked_time: 0 }),
+        ("user2", LockInfo { amount: Uint128::from(100u128), last_locked_time: 40, last_unlocked_time: 0 }),
+        ("user3", LockInfo { amount: Uint128::from(100u128), last_locked_time: 20, last_unlocked_time: 0 }),
+    ])]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        lock_requirement: Some(LockRequirementInfo {
+            locking: "locking".to_string(),
+            min_amount: Uint128::zero(),
+            snapshot_time: 30,
+        }),
+        ..mock_instantiate_msg()
+    };
+
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.lock_requirement.as_mut().unwrap().min_amount = Uint128::from(100u128);
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone()).unwrap();
+    let presale_info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(presale_info.lock_requirement, init_msg.lock_requirement);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let fund = [Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }];
+
+    // not enough locked
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &fund), msg.clone());
+    assert_eq!(res, Err(ContractError::NotEligible {}));
+
+    // locked after the snapshot
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &fund), msg.clone());
+    assert_eq!(res, Err(ContractError::NotEligible {}));
+
+    // never locked
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user4", &fund), msg.clone());
+    assert_eq!(res, Err(ContractError::NotEligible {}));
+
+    execute(deps.as_mut(), env, mock_info("user3", &fund), msg).unwrap();
+}
+
+#[test]
+fn test_lottery() {
+    let mut deps = mock_dependencies(&[]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Lottery, ..mock_round("", 100, 200) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        lottery: Some(LotteryInfo {
+            registration_end_time: 50,
+            reveal_deadline: 50,
+            winners_count: 2,
+            allocation: Uint128::from(100u128),
+            // commitments are stored in lower case
+            commitment: lottery_commitment("secret").to_uppercase(),
+        }),
+        ..mock_instantiate_msg()
+    };
+
+    // the reveal deadline follows the registration and passes before the lottery rounds start
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+    init_msg.lottery.as_mut().unwrap().reveal_deadline = 100;
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.lottery.as_mut().unwrap().reveal_deadline = 80;
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(10);
+    for user in ["user1", "user2", "user3", "user4", "user5"] {
+        execute(deps.as_mut(), env.clone(), mock_info(user, &[]), ExecuteMsg::Register {}).unwrap();
+    }
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), ExecuteMsg::Register {});
+    assert_eq!(res, Err(ContractError::AlreadyRegistered {}));
+
+    // the draw waits for registration to close
+    let msg = ExecuteMsg::RevealLottery { secret: "secret".to_string() };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::StillInProgress {}));
+
+    env.block.time = Timestamp::from_seconds(60);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user6", &[]), ExecuteMsg::Register {});
+    assert_eq!(res, Err(ContractError::RegistrationClosed {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::RevealLottery { secret: "other".to_string() });
+    assert_eq!(res, Err(ContractError::VerificationFailed {}));
+
+    let verify: VerifyLotteryResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::VerifyLottery {}).unwrap()).unwrap();
+    assert!(!verify.valid);
+
+    // the seed mixes the secret with the registrations
+    let hash = ["user1", "user2", "user3", "user4", "user5"].iter().fold(String::new(), |hash, user| registrations_hash(&hash, user, env.block.height, 10));
+    let seed = lottery_seed("secret", &hash);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone()).unwrap();
+    assert_eq!(res.attributes, vec![
+        attr("action", "reveal_lottery"),
+        attr("seed", hex::encode(seed)),
+        attr("winners_count", "2"),
+    ]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::AlreadyDrawn {}));
+
+    let lottery: LotteryResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Lottery {}).unwrap()).unwrap();
+    let lottery = lottery.lottery.unwrap();
+    assert_eq!(lottery.registrants_count, 5);
+    assert_eq!(lottery.registrations_hash, hash);
+    assert_eq!(lottery.commitment, lottery_commitment("secret"));
+    assert_eq!(lottery.draw.unwrap().seed, hex::encode(seed));
+
+    // anyone can draw again from the revealed secret
+    let verify: VerifyLotteryResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::VerifyLottery {}).unwrap()).unwrap();
+    assert!(verify.valid);
+
+    let mut expected: Vec<String> = draw_winners(&seed, 5, 2).into_iter().map(|id| format!("user{}", id + 1)).collect();
+    expected.sort();
+    let msg = QueryMsg::LotteryWinners { start_after: None, limit: Some(1) };
+    let res: LotteryWinnersResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.winners.len(), 1);
+    assert_eq!(res.winners[0].address, expected[0]);
+    assert_eq!(res.winners[0].allocation, Uint128::from(100u128));
+    let msg = QueryMsg::LotteryWinners { start_after: Some(expected[0].clone()), limit: None };
+    let res: LotteryWinnersResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.winners.iter().map(|winner| winner.address.clone()).collect::<Vec<String>>(), vec![expected[1].clone()]);
+
+    // winners buy up to the lottery allocation without a proof
+    env.block.time = Timestamp::from_seconds(150);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let loser = ["user1", "user2", "user3", "user4", "user5"].iter().find(|user| !expected.contains(&user.to_string())).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info(loser, &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(&expected[0], &[Coin { denom: "uusd".to_string(), amount: Uint128::from(101u128) }]), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+    execute(deps.as_mut(), env, mock_info(&expected[0], &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg).unwrap();
+
+    // a secret withheld past the reveal deadline cancels the lottery rounds,
+    // the registrants could otherwise compute a draw from the public registrations
+    let mut deps = mock_dependencies(&[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(10);
+    for user in ["user1", "user2", "user3"] {
+        execute(deps.as_mut(), env.clone(), mock_info(user, &[]), ExecuteMsg::Register {}).unwrap();
+    }
+
+    env.block.time = Timestamp::from_seconds(81);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::RevealLottery { secret: "secret".to_string() });
+    assert_eq!(res, Err(ContractError::RevealDeadlinePassed {}));
+
+    let lottery: LotteryResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Lottery {}).unwrap()).unwrap();
+    assert_eq!(lottery.lottery.unwrap().draw, None);
+    let res: LotteryWinnersResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::LotteryWinners { start_after: None, limit: None }).unwrap()).unwrap();
+    assert!(res.winners.is_empty());
+    let verify: VerifyLotteryResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::VerifyLottery {}).unwrap()).unwrap();
+    assert!(!verify.valid);
+
+    // even the registrants who would win a draw seeded by the registrations alone
+    env.block.time = Timestamp::from_seconds(150);
+    let hash = ["user1", "user2", "user3"].iter().fold(String::new(), |hash, user| registrations_hash(&hash, user, env.block.height, 10));
+    let precomputed_seed: [u8; 32] = sha2::Sha256::digest(hash.as_bytes()).into();
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    for registration_id in draw_winners(&precomputed_seed, 3, 2) {
+        let user = format!("user{}", registration_id + 1);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(&user, &[Coin { denom: "uusd".to_string(), amount: Uint128::from(10u128) }]), msg.clone());
+        assert_eq!(res, Err(ContractError::LotteryCancelled {}));
+    }
+}
+
+#[test]
+fn test_draw_winners() {
+    let seed = lottery_seed("secret", &registrations_hash("", "user1", 12345, 1571797419));
+
+    // the draw is a permutation prefix of distinct registrants
+    let mut winners = draw_winners(&seed, 100, 30);
+    assert_eq!(winners, draw_winners(&seed, 100, 30));
+    winners.sort_unstable();
+    winners.dedup();
+    assert_eq!(winners.len(), 30);
+    assert!(winners.iter().all(|id| *id < 100));
+
+    // everyone wins when there are no more registrants than winners
+    let mut winners = draw_winners(&seed, 3, 5);
+    winners.sort_unstable();
+    assert_eq!(winners, vec![0, 1, 2]);
+    assert!(draw_winners(&seed, 0, 5).is_empty());
+
+    // every registration changes the draw
+    assert_ne!(draw_winners(&lottery_seed("secret", &registrations_hash("", "user1", 12346, 1571797419)), 100, 30), draw_winners(&seed, 100, 30));
+    assert_ne!(draw_winners(&lottery_seed("secret", &registrations_hash("", "user2", 12345, 1571797419)), 100, 30), draw_winners(&seed, 100, 30));
+}
+
+#[test]
+fn test_direct_claim() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[("reward_token", &[(MOCK_CONTRACT_ADDR, Uint128::from(1000u128))])]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        vesting: None,
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+
+    // the claim start time is required without vesting
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    init_msg.claim_start_time = Some(500);
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    // nothing is sent to a vesting contract
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg).unwrap();
+    assert!(res.messages.is_empty());
+
+    env.block.time = Timestamp::from_seconds(101);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::Claim {});
+    assert_eq!(res, Err(ContractError::ClaimNotStarted {}));
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+
+    // the unsold tokens are withdrawn from the presale
+    let msg = ExecuteMsg::WithdrawUnsoldToken { receiver: "owner".to_string() };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "reward_token".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "owner".to_string(),
+            amount: Uint128::from(900u128),
+        }).unwrap(),
+        funds: vec![],
+    }))]);
+
+    env.block.time = Timestamp::from_seconds(500);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Claim {});
+    assert_eq!(res, Err(ContractError::NothingToClaim {}));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::Claim {}).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "reward_token".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "user".to_string(),
+            amount: Uint128::from(100u128),
+        }).unwrap(),
+        funds: vec![],
+    }))]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::Claim {});
+    assert_eq!(res, Err(ContractError::NothingToClaim {}));
+
+    // claimed tokens are no longer owed
+    deps.querier.with_token_balances(&[("reward_token", &[(MOCK_CONTRACT_ADDR, Uint128::from(900u128))])]);
+    let msg = ExecuteMsg::WithdrawUnsoldToken { receiver: "owner".to_string() };
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "reward_token".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "owner".to_string(),
+            amount: Uint128::from(900u128),
+        }).unwrap(),
+        funds: vec![],
+    }))]);
+}
+
+#[test]
+fn test_cancel_sale() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[("reward_token", &[("vesting", Uint128::from(1000u128))])]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg.clone()).unwrap();
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::CancelSale {});
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    let clear_msg = ExecuteMsg::ClearVesting { start_after: Some("user".to_string()), limit: Some(30) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), clear_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    // sold tokens go back to the owner as well
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::CancelSale {}).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::ClearRecipients { start_after: None, limit: None }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: "vesting".to_string(),
+                recipient: "owner".to_string(),
+                amount: Uint128::from(1000u128),
+            }).unwrap(),
+            funds: vec![],
+        })),
+    ]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::CancelSale {});
+    assert_eq!(res, Err(ContractError::SaleCancelled {}));
+
+    // the next pages of vesting recipients are cleared by the owner
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), clear_msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), clear_msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "vesting".to_string(),
+        msg: to_binary(&vesting::msg::ExecuteMsg::ClearRecipients { start_after: Some("user".to_string()), limit: Some(30) }).unwrap(),
+        funds: vec![],
+    }))]);
+
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.status, SaleStatus::Cancelled);
+
+    // deposits are frozen and the sale can no longer be finalized
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), msg);
+    assert_eq!(res, Err(ContractError::SaleCancelled {}));
+    env.block.time = Timestamp::from_seconds(101);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {});
+    assert_eq!(res, Err(ContractError::AlreadyFinalized {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::WithdrawFunds { receiver: "owner".to_string() });
+    assert_eq!(res, Err(ContractError::SaleCancelled {}));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), ExecuteMsg::ClaimRefund {}).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user".to_string(),
+        amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }],
+    }))]);
+    let res = execute(deps.as_mut(), env, mock_info("user", &[]), ExecuteMsg::ClaimRefund {});
+    assert_eq!(res, Err(ContractError::NothingToRefund {}));
+}
+
+#[test]
+fn test_withdraw_contribution() {
+    let mut deps = mock_dependencies(&[]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![
+            RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 0, 100) },
+            RoundInfo { allocation_type: AllocationType::Public, price_multiplier: Decimal::percent(200), ..mock_round("", 101, 200) },
+        ],
+        total_rewards_amount: Uint128::from(2000u128),
+        cool_off: Some(CoolOff { period: 0, fee_bps: 100 }),
+        ..mock_instantiate_msg()
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+    init_msg.cool_off = Some(CoolOff { period: 20, fee_bps: 100 });
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let uusd = FundAsset::Native { denom: "uusd".to_string() };
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1000u128) }]), msg).unwrap();
+
+    let msg = ExecuteMsg::WithdrawContribution { round_id: 0, asset: uusd.clone(), amount: Uint128::from(1001u128) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg);
+    assert_eq!(res, Err(ContractError::ExceedContribution {}));
+    let msg = ExecuteMsg::WithdrawContribution { round_id: 0, asset: uusd.clone(), amount: Uint128::from(100u128) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedContribution {}));
+
+    // the fee goes to the treasury and the vesting amount follows the remaining contribution
+    env.block.time = Timestamp::from_seconds(70);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: "user".to_string(),
+                amount: 900,
+            }).unwrap(),
+            funds: vec![],
+        })),
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(1u128) }],
+        })),
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(99u128) }],
+        })),
+    ]);
+
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.fund_balance, Uint128::from(900u128));
+    assert_eq!(participant.data.fund_balances, vec![Asset { info: uusd.clone(), amount: Uint128::from(900u128) }]);
+    assert_eq!(participant.data.reward_balance, Uint128::from(900u128));
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.sold_amount, Uint128::from(900u128));
+
+    // the cool-off period runs from the first deposit into the round
+    env.block.time = Timestamp::from_seconds(71);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::CoolOffNotAvailable {}));
+    let deposit = |round_id: u64| ExecuteMsg::Deposit { round_id, allocation: Uint128::zero(), proof: vec![], whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(1u128) }]), deposit(0)).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::CoolOffNotAvailable {}));
+
+    // the withdrawal gives back the tokens bought in the round at its price
+    env.block.time = Timestamp::from_seconds(150);
+    execute(deps.as_mut(), env.clone(), mock_info("user", &[Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }]), deposit(1)).unwrap();
+    let msg = ExecuteMsg::WithdrawContribution { round_id: 1, asset: uusd.clone(), amount: Uint128::from(100u128) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone()).unwrap();
+    assert_eq!(res.attributes[4], attr("reward_amount", "50"));
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetParticipant { user: "user".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.fund_balance, Uint128::from(901u128));
+    assert_eq!(participant.data.reward_balance, Uint128::from(901u128));
+    let status: GetSaleStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::GetSaleStatus {}).unwrap()).unwrap();
+    assert_eq!(status.sold_amount, Uint128::from(901u128));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::ExceedContribution {}));
+
+    // withdrawals end with the presale
+    env.block.time = Timestamp::from_seconds(201);
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::Finalize {}).unwrap();
+    let res = execute(deps.as_mut(), env, mock_info("user", &[]), msg);
+    assert_eq!(res, Err(ContractError::AlreadyFinalized {}));
+}
+
+// Whitelist test vectors shared with the tree builder of the scripts
+#[derive(Deserialize)]
+struct WhitelistVectors {
+    leaves: Vec<LeafVector>,
+    trees: Vec<TreeVector>,
+}
+
+#[derive(Deserialize)]
+struct LeafVector {
+    format: LeafFormat,
+    chain_id: String,
+    contract: String,
+    round_id: u64,
+    address: String,
+    allocation: Uint128,
+    leaf: String,
+}
+
+#[derive(Deserialize)]
+struct TreeVector {
+    format: LeafFormat,
+    chain_id: String,
+    contract: String,
+    round_id: u64,
+    root: String,
+    proofs: Vec<ProofVector>,
+}
+
+#[derive(Deserialize)]
+struct ProofVector {
+    address: String,
+    allocation: Uint128,
+    proof: Vec<String>,
+}
+
+#[test]
+fn test_whitelist_vectors() {
+    let vectors: WhitelistVectors = from_slice(include_bytes!("../testdata/whitelist_vectors.json")).unwrap();
+
+    for v in vectors.leaves.iter() {
+        let ctx = LeafContext { chain_id: &v.chain_id, contract: &v.contract, round_id: v.round_id };
+        assert_eq!(hex::encode(whitelist_leaf(&v.format, &ctx, &v.address, v.allocation)), v.leaf);
+    }
+
+    // plain leaves cannot tell the address from the allocation, v1 leaves can
+    let ctx = LeafContext { chain_id: "juno-1", contract: "juno1presale", round_id: 0 };
+    assert_eq!(
+        whitelist_leaf(&LeafFormat::Plain, &ctx, "juno1user", Uint128::from(123u128)),
+        whitelist_leaf(&LeafFormat::Plain, &ctx, "juno1user1", Uint128::from(23u128)),
+    );
+    assert_ne!(
+        whitelist_leaf(&LeafFormat::V1, &ctx, "juno1user", Uint128::from(123u128)),
+        whitelist_leaf(&LeafFormat::V1, &ctx, "juno1user1", Uint128::from(23u128)),
+    );
+
+    for tree in vectors.trees.iter() {
+        let ctx = LeafContext { chain_id: &tree.chain_id, contract: &tree.contract, round_id: tree.round_id };
+        for p in tree.proofs.iter() {
+            let leaf = whitelist_leaf(&tree.format, &ctx, &p.address, p.allocation);
+            assert!(verify_whitelist(&tree.root, leaf, &p.proof).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_whitelist_leaf_format() {
+    let vectors: WhitelistVectors = from_slice(include_bytes!("../testdata/whitelist_vectors.json")).unwrap();
+    // the tree is built for the round 0 of the presale in the mock environment
+    let tree = &vectors.trees[0];
+    assert_eq!((tree.chain_id.as_str(), tree.contract.as_str()), (mock_env().block.chain_id.as_str(), MOCK_CONTRACT_ADDR));
+
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round(&tree.root, 0, 100)],
+        total_rewards_amount: Uint128::from(1000u128),
+        ..mock_instantiate_msg()
+    };
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    // new presales use the v1 leaves unless told otherwise
+    let info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(info.whitelist_leaf_format, LeafFormat::V1);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let proof = &tree.proofs[0];
+    let funds = [Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }];
+
+    // the allocation is bound to the proof
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(200u128), proof: proof.proof.clone(), whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(&proof.address, &funds), msg);
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: proof.allocation, proof: proof.proof.clone(), whitelist_id: None, ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info(&proof.address, &funds), msg).unwrap();
+
+    // and so is the chain
+    env.block.chain_id = "juno-1".to_string();
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: tree.proofs[1].allocation, proof: tree.proofs[1].proof.clone(), whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env, mock_info(&tree.proofs[1].address, &funds), msg);
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+}
+
+#[test]
+fn test_whitelists() {
+    let env = mock_env();
+    let ctx = LeafContext { chain_id: &env.block.chain_id, contract: MOCK_CONTRACT_ADDR, round_id: 0 };
+    let leaves = [
+        whitelist_leaf(&LeafFormat::V1, &ctx, "user", Uint128::from(100u128)),
+        whitelist_leaf(&LeafFormat::V1, &ctx, "user2", Uint128::from(200u128)),
+    ];
+    let mut sorted_leaves = leaves;
+    sorted_leaves.sort_unstable();
+    let partners_root = hex::encode(sha2::Sha256::digest(sorted_leaves.concat()));
+    // a single leaf is its own root
+    let community_root = hex::encode(whitelist_leaf(&LeafFormat::V1, &ctx, "user3", Uint128::from(300u128)));
+
+    let mut deps = mock_dependencies(&[]);
+    let partners = WhitelistInfo {
+        id: "partners".to_string(),
+        merkle_root: partners_root.clone(),
+        round_ids: vec![1],
+        cap: Some(Uint128::from(150u128)),
+    };
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round(&community_root, 0, 100), RoundInfo { allocation_type: AllocationType::Public, ..mock_round("", 101, 200) }],
+        total_rewards_amount: Uint128::from(1000u128),
+        whitelists: vec![partners.clone()],
+        ..mock_instantiate_msg()
+    };
+
+    // whitelists are open to guaranteed and fcfs rounds only
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+    init_msg.whitelists[0].round_ids = vec![0];
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let funds = |amount: u128| [Coin { denom: "uusd".to_string(), amount: Uint128::from(amount) }];
+    let deposit = |allocation: u128, proof: &[u8; 32], whitelist_id: &str| ExecuteMsg::Deposit {
+        round_id: 0,
+        allocation: Uint128::from(allocation),
+        proof: vec![hex::encode(proof)],
+        whitelist_id: Some(whitelist_id.to_string()),
+        ticket: None,
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds(100)), deposit(100, &leaves[1], "kol"));
+    assert_eq!(res, Err(ContractError::WhitelistNotFound {}));
+    // the proof of the list does not verify against the round merkle root
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(100u128), proof: vec![hex::encode(leaves[1])], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds(100)), msg);
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+    execute(deps.as_mut(), env.clone(), mock_info("user", &funds(100)), deposit(100, &leaves[1], "partners")).unwrap();
+
+    // the list cap trims the deposit
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &funds(200)), deposit(200, &leaves[0], "partners")).unwrap();
+    assert_eq!(res.attributes[2], attr("refund_amount", "150"));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &funds(100)), deposit(200, &leaves[0], "partners"));
+    assert_eq!(res, Err(ContractError::WhitelistCapReached {}));
+
+    let msg = ExecuteMsg::AddWhitelist {
+        whitelist: WhitelistInfo { id: "community".to_string(), merkle_root: community_root.clone(), round_ids: vec![0], cap: None },
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::WhitelistExists {}));
+
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(300u128), proof: vec![], whitelist_id: Some("community".to_string()), ticket: None };
+    execute(deps.as_mut(), env.clone(), mock_info("user3", &funds(300)), msg).unwrap();
+
+    let res: WhitelistsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Whitelists { start_after: None, limit: None }).unwrap()).unwrap();
+    assert_eq!(res.whitelists, vec![
+        WhitelistResponse { id: "community".to_string(), merkle_root: community_root, round_ids: vec![0], cap: None, sold_amount: Uint128::from(300u128) },
+        WhitelistResponse { id: "partners".to_string(), merkle_root: partners_root, round_ids: vec![0], cap: partners.cap, sold_amount: Uint128::from(150u128) },
+    ]);
+    let res: WhitelistResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::Whitelist { id: "partners".to_string() }).unwrap()).unwrap();
+    assert_eq!(res.sold_amount, Uint128::from(150u128));
+}
+
+#[test]
+fn test_kyc_ticket() {
+    let secp256k1_key = k256::ecdsa::SigningKey::from_bytes(&[1u8; 32]).unwrap();
+    let ed25519_key = ed25519_zebra::SigningKey::from([2u8; 32]);
+
+    let mut deps = mock_dependencies(&[]);
+    let mut init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "uusd".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 0, 100)],
+        total_rewards_amount: Uint128::from(1000u128),
+        kyc_signer: Some(KycSigner { key_type: KycKeyType::Ed25519, public_key: Binary::from(secp256k1_key.verifying_key().to_bytes().to_vec()) }),
+        ..mock_instantiate_msg()
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+    init_msg.kyc_signer.as_mut().unwrap().key_type = KycKeyType::Secp256k1;
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), init_msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let ctx = LeafContext { chain_id: &env.block.chain_id, contract: MOCK_CONTRACT_ADDR, round_id: 0 };
+    let allocation = Uint128::from(500u128);
+    let secp256k1_ticket = |user: &str, expires_at: u64, nonce: u64| {
+        let signature: k256::ecdsa::Signature = k256::ecdsa::signature::Signer::sign(&secp256k1_key, &ticket_message(&ctx, user, allocation, expires_at, nonce));
+        AllocationTicket { expires_at, nonce, signature: Binary::from(signature.as_ref()) }
+    };
+    let deposit = |ticket: AllocationTicket| ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: Some(ticket) };
+    let funds = [Coin { denom: "uusd".to_string(), amount: Uint128::from(100u128) }];
+
+    // without a merkle root, deposits require a ticket
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds), msg);
+    assert_eq!(res, Err(ContractError::NotWhitelisted {}));
+
+    // the ticket replaces the merkle proof
+    execute(deps.as_mut(), env.clone(), mock_info("user", &funds), deposit(secp256k1_ticket("user", 60, 1))).unwrap();
+    let res: TicketNonceResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::TicketNonce { nonce: 1 }).unwrap()).unwrap();
+    assert_eq!(res, TicketNonceResponse { used_by: Some("user".to_string()), redeemed_value: Uint128::from(100u128) });
+
+    // the ticket is filled over several deposits, its nonce stays with the account
+    execute(deps.as_mut(), env.clone(), mock_info("user", &funds), deposit(secp256k1_ticket("user", 60, 1))).unwrap();
+    let res: TicketNonceResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::TicketNonce { nonce: 1 }).unwrap()).unwrap();
+    assert_eq!(res.redeemed_value, Uint128::from(200u128));
+    let over_funds = [Coin { denom: "uusd".to_string(), amount: Uint128::from(301u128) }];
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &over_funds), deposit(secp256k1_ticket("user", 60, 1)));
+    assert_eq!(res, Err(ContractError::ExceedAllocation {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &funds), deposit(secp256k1_ticket("user2", 60, 1)));
+    assert_eq!(res, Err(ContractError::NonceUsed {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user2", &funds), deposit(secp256k1_ticket("user", 60, 2)));
+    assert_eq!(res, Err(ContractError::InvalidTicket {}));
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds), deposit(secp256k1_ticket("user", 49, 2)));
+    assert_eq!(res, Err(ContractError::TicketExpired {}));
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation, proof: vec![], whitelist_id: Some("partners".to_string()), ticket: Some(secp256k1_ticket("user", 60, 2)) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds), msg);
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    // rotating the signer invalidates the tickets of the previous one
+    let ed25519_signer = KycSigner {
+        key_type: KycKeyType::Ed25519,
+        public_key: Binary::from(ed25519_zebra::VerificationKey::from(&ed25519_key).as_ref()),
+    };
+    let msg = ExecuteMsg::SetKycSigner { signer: Some(ed25519_signer.clone()) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+    let info: PresaleInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PresaleInfo {}).unwrap()).unwrap();
+    assert_eq!(info.kyc_signer, Some(ed25519_signer));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &funds), deposit(secp256k1_ticket("user", 60, 2)));
+    assert_eq!(res, Err(ContractError::InvalidTicket {}));
+    let signature = ed25519_key.sign(&ticket_message(&ctx, "user", allocation, 60, 2));
+    let ticket = AllocationTicket { expires_at: 60, nonce: 2, signature: Binary::from(<[u8; 64]>::from(signature).to_vec()) };
+    execute(deps.as_mut(), env.clone(), mock_info("user", &funds), deposit(ticket)).unwrap();
+
+    let participant: GetParticipantResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::GetParticipant { user: "user".to_string() }).unwrap()).unwrap();
+    assert_eq!(participant.data.fund_balance, Uint128::from(300u128));
+}
+
+#[test]
+fn test_update_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    let init_msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Native { denom: "ibc/atom".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        rounds: vec![mock_round("", 100, 1000)],
+        total_rewards_amount: Uint128::from(1000000000000000000000u128),
+        ..mock_instantiate_msg()
+    };
+    let info = mock_info("owner", &[]);
+
+    // queried token decimals are bounded like the ones set by the owner
+    deps.querier.with_token_decimals("reward_token", 19);
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg.clone());
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+    mock_baseline_presale(&mut deps.storage, "", &[("user1", 150, 100)]);
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_decimals("reward_token", 18);
+    deps.querier.with_token_decimals("fund_token", 19);
+    let msg = InstantiateMsg {
+        accepted_assets: vec![AcceptedAssetInfo { info: FundAsset::Cw20 { contract_addr: "fund_token".to_string() }, exchange_rate: Uint128::from(ACCURACY) }],
+        ..init_msg.clone()
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::InvalidInput {}));
+
+    let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    // the reward decimals are queried once, native assets default to 6 decimals
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap();
+    let presale_info: PresaleInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(presale_info.reward_decimals, 18);
+    assert_eq!(presale_info.accepted_assets[0].decimals, 6);
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(50);
+    let msg = ExecuteMsg::UpdateDecimals {
+        reward_decimals: None,
+        fund_decimals: vec![FundDecimals { info: FundAsset::Native { denom: "ibc/atom".to_string() }, decimals: 18 }],
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg.clone());
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::UpdateDecimals {
+        reward_decimals: None,
+        fund_decimals: vec![FundDecimals { info: FundAsset::Native { denom: "uusd".to_string() }, decimals: 18 }],
+    });
+    assert_eq!(res.unwrap_err(), ContractError::InvalidInput {});
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::UpdateDecimals { reward_decimals: Some(19), fund_decimals: vec![] });
+    assert_eq!(res.unwrap_err(), ContractError::InvalidInput {});
+
+    let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PresaleInfo {}).unwrap();
+    let presale_info: PresaleInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(presale_info.accepted_assets[0].decimals, 18);
+
+    // decimals are fixed once the sale starts
+    env.block.time = Timestamp::from_seconds(100);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res.unwrap_err(), ContractError::SaleStarted {});
+
+    // 2 fund tokens with 18 decimals buy 2 reward tokens with 18 decimals
+    let info = mock_info("user", &[Coin { denom: "ibc/atom".to_string(), amount: Uint128::from(2000000000000000000u128) }]);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(10000000u128), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vesting".to_string(),
+            msg: to_binary(&vesting::msg::ExecuteMsg::UpdateRecipient {
+                recp: "user".to_string(),
+                amount: 2000000000000000000,
+            }).unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // vesting schedules hold u64 amounts, about 18.4 tokens with 18 decimals
+    let info = mock_info("user", &[Coin { denom: "ibc/atom".to_string(), amount: Uint128::from(18000000000000000000u128) }]);
+    let msg = ExecuteMsg::Deposit { round_id: 0, allocation: Uint128::from(100000000u128), proof: vec![], whitelist_id: None, ticket: None };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(res.unwrap_err(), ContractError::ExceedVestingAmount {});
+
+    // the price calculations do not overflow with 18 decimals on both sides
+    let state = read_state(&deps.storage).unwrap();
+    let amount = Uint128::from(1000000000000000000000u128);
+    assert_eq!(calc_reward_amount(&state, &state.accepted_assets[0], Decimal::one(), amount), Ok(amount));
+    assert_eq!(calc_fund_amount(&state, &state.accepted_assets[0], Decimal::one(), amount), Ok(amount));
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::{Api, Uint128};
+use sha2::{Digest, Sha256};
+
+use crate::merkle::{push_prefixed, LeafContext};
+use crate::state::{KycKeyType, KycSigner};
+
+/// Domain tag of the allocation tickets, so that their signatures cannot be replayed as other messages.
+pub const ALLOCATION_TICKET_DOMAIN: &str = "presale/allocation-ticket/v1";
+
+/// Message signed by the KYC signer to grant `allocation` to `sender` in the given round until `expires_at`.
+///
+/// The domain tag, chain id, presale address, round id, sender, allocation, expiry and nonce are encoded
+/// like the v1 whitelist leaves, strings prefixed by their big-endian u32 byte length and numbers as
+/// fixed-width big-endian integers. Secp256k1 signers sign its sha256 hash, ed25519 signers the message itself.
+pub fn ticket_message(ctx: &LeafContext, sender: &str, allocation: Uint128, expires_at: u64, nonce: u64) -> Vec<u8> {
+    let mut message = vec![];
+    push_prefixed(&mut message, ALLOCATION_TICKET_DOMAIN);
+    push_prefixed(&mut message, ctx.chain_id);
+    push_prefixed(&mut message, ctx.contract);
+    message.extend_from_slice(&ctx.round_id.to_be_bytes());
+    push_prefixed(&mut message, sender);
+    message.extend_from_slice(&allocation.u128().to_be_bytes());
+    message.extend_from_slice(&expires_at.to_be_bytes());
+    message.extend_from_slice(&nonce.to_be_bytes());
+    message
+}
+
+/// Whether the signature of the ticket message is valid for the KYC signer, malformed signatures are not.
+pub fn verify_ticket(api: &dyn Api, signer: &KycSigner, message: &[u8], signature: &[u8]) -> bool {
+    let verified = match signer.key_type {
+        KycKeyType::Secp256k1 => api.secp256k1_verify(&Sha256::digest(message), signature, &signer.public_key),
+        KycKeyType::Ed25519 => api.ed25519_verify(message, signature, &signer.public_key),
+    };
+    verified.unwrap_or(false)
+}
@@ -1,8 +1,11 @@
 
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Order;
+use cosmwasm_std::{to_binary, BankMsg, Coin, CosmosMsg, Order, StdError, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -20,3 +23,82 @@ impl From<OrderBy> for Order {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FundAsset {
+    Native { denom: String },
+    Cw20 { contract_addr: String },
+}
+
+impl FundAsset {
+    pub fn transfer_msg(&self, recipient: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        Ok(match self {
+            FundAsset::Native { denom } => CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient,
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            }),
+            FundAsset::Cw20 { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient, amount })?,
+                funds: vec![],
+            }),
+        })
+    }
+}
+
+impl fmt::Display for FundAsset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FundAsset::Native { denom } => write!(f, "{}", denom),
+            FundAsset::Cw20 { contract_addr } => write!(f, "{}", contract_addr),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Asset {
+    pub info: FundAsset,
+    pub amount: Uint128,
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.info)
+    }
+}
+
+/// Amount of the entry of `info`, zero when missing.
+pub fn asset_amount(assets: &[Asset], info: &FundAsset) -> Uint128 {
+    assets
+        .iter()
+        .find(|asset| &asset.info == info)
+        .map(|asset| asset.amount)
+        .unwrap_or_default()
+}
+
+/// Subtracts `amount` from the entry of `info`, which has to hold at least `amount`.
+pub fn sub_asset_amount(assets: &mut [Asset], info: &FundAsset, amount: Uint128) -> StdResult<()> {
+    match assets.iter_mut().find(|asset| &asset.info == info) {
+        Some(asset) => {
+            asset.amount = asset.amount.checked_sub(amount)?;
+            Ok(())
+        }
+        None if amount.is_zero() => Ok(()),
+        None => Err(StdError::generic_err("asset not found")),
+    }
+}
+
+/// Adds `amount` to the entry of `info`, inserting the entry when missing.
+pub fn add_asset_amount(assets: &mut Vec<Asset>, info: &FundAsset, amount: Uint128) {
+    match assets.iter_mut().find(|asset| &asset.info == info) {
+        Some(asset) => asset.amount += amount,
+        None => assets.push(Asset {
+            info: info.clone(),
+            amount,
+        }),
+    }
+}
@@ -0,0 +1,280 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, attr, Api, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+use pagination::filtered_page;
+use versioning::{assert_migration, migration_attributes};
+
+use presale::msg::{GetSaleStatusResponse, PresaleInfoResponse, QueryMsg as PresaleQueryMsg};
+use presale::state::SaleStatus;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, ProjectInfo, ProjectResponse, ProjectsResponse, QueryMsg};
+use crate::state::{CONFIG, PROJECTS, PROJECT_COUNT, Config, Project, ProjectStatus};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:registry";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        curators: msg.curators
+            .iter()
+            .map(|curator| deps.api.addr_canonicalize(curator))
+            .collect::<StdResult<Vec<CanonicalAddr>>>()?,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    PROJECT_COUNT.save(deps.storage, &0u64)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION)))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, info, owner),
+        ExecuteMsg::AddCurator { curator } => execute_add_curator(deps, info, curator),
+        ExecuteMsg::RemoveCurator { curator } => execute_remove_curator(deps, info, curator),
+        ExecuteMsg::RegisterProject { project } => execute_register_project(deps, info, project),
+        ExecuteMsg::UpdateProject { project_id, project } => execute_update_project(deps, info, project_id, project),
+        ExecuteMsg::RemoveProject { project_id } => execute_remove_project(deps, info, project_id),
+    }
+}
+
+pub fn execute_update_config(deps: DepsMut, info: MessageInfo, owner: Option<String>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn execute_add_curator(deps: DepsMut, info: MessageInfo, curator: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let curator_raw = deps.api.addr_canonicalize(&curator)?;
+    if config.curators.contains(&curator_raw) {
+        return Err(ContractError::CuratorExists {});
+    }
+    config.curators.push(curator_raw);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_curator"),
+        attr("curator", curator),
+    ]))
+}
+
+pub fn execute_remove_curator(deps: DepsMut, info: MessageInfo, curator: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let curator_raw = deps.api.addr_canonicalize(&curator)?;
+    if !config.curators.contains(&curator_raw) {
+        return Err(ContractError::CuratorNotFound {});
+    }
+    config.curators.retain(|addr| addr != &curator_raw);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_curator"),
+        attr("curator", curator),
+    ]))
+}
+
+// The owner curates as well.
+fn assert_curator(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender_raw != config.owner && !config.curators.contains(&sender_raw) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn canonicalize_option(api: &dyn Api, addr: Option<String>) -> StdResult<Option<CanonicalAddr>> {
+    addr.map(|addr| api.addr_canonicalize(&addr)).transpose()
+}
+
+// The presale has to answer the status queries.
+fn project_from_info(deps: Deps, project: ProjectInfo) -> Result<Project, ContractError> {
+    if project.name.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let presale = deps.api.addr_canonicalize(&project.presale)?;
+    let _: GetSaleStatusResponse = deps.querier.query_wasm_smart(project.presale, &PresaleQueryMsg::GetSaleStatus {})?;
+    Ok(Project {
+        name: project.name,
+        logo_url: project.logo_url,
+        description: project.description,
+        socials: project.socials,
+        token: canonicalize_option(deps.api, project.token)?,
+        presale,
+        vesting: canonicalize_option(deps.api, project.vesting)?,
+        staking: canonicalize_option(deps.api, project.staking)?,
+        locking: canonicalize_option(deps.api, project.locking)?,
+    })
+}
+
+/// Lifecycle status of a project from the sale status and schedule of its presale.
+pub fn query_project_status(deps: Deps, env: &Env, presale: &CanonicalAddr) -> StdResult<ProjectStatus> {
+    let presale = deps.api.addr_humanize(presale)?;
+    let sale_status: GetSaleStatusResponse = deps.querier.query_wasm_smart(&presale, &PresaleQueryMsg::GetSaleStatus {})?;
+    let status = match sale_status.status {
+        SaleStatus::Succeeded => ProjectStatus::Distributing,
+        SaleStatus::Failed | SaleStatus::Cancelled => ProjectStatus::Ended,
+        SaleStatus::Pending => {
+            let presale_info: PresaleInfoResponse = deps.querier.query_wasm_smart(&presale, &PresaleQueryMsg::PresaleInfo {})?;
+            let now = env.block.time.seconds();
+            if now < presale_info.start_time {
+                ProjectStatus::Upcoming
+            } else if now <= presale_info.end_time {
+                ProjectStatus::Live
+            } else {
+                ProjectStatus::Ended
+            }
+        }
+    };
+    Ok(status)
+}
+
+pub fn execute_register_project(deps: DepsMut, info: MessageInfo, project: ProjectInfo) -> Result<Response, ContractError> {
+    assert_curator(deps.as_ref(), &info)?;
+
+    let project = project_from_info(deps.as_ref(), project)?;
+    let project_id = PROJECT_COUNT.load(deps.storage)?;
+    PROJECTS.save(deps.storage, project_id, &project)?;
+    PROJECT_COUNT.save(deps.storage, &(project_id + 1))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_project"),
+        attr("project_id", project_id.to_string()),
+        attr("name", project.name),
+    ]))
+}
+
+pub fn execute_update_project(deps: DepsMut, info: MessageInfo, project_id: u64, project: ProjectInfo) -> Result<Response, ContractError> {
+    assert_curator(deps.as_ref(), &info)?;
+
+    // only listed projects are updated
+    PROJECTS.load(deps.storage, project_id)?;
+    let project = project_from_info(deps.as_ref(), project)?;
+    PROJECTS.save(deps.storage, project_id, &project)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_project"),
+        attr("project_id", project_id.to_string()),
+    ]))
+}
+
+pub fn execute_remove_project(deps: DepsMut, info: MessageInfo, project_id: u64) -> Result<Response, ContractError> {
+    assert_curator(deps.as_ref(), &info)?;
+
+    PROJECTS.load(deps.storage, project_id)?;
+    PROJECTS.remove(deps.storage, project_id);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_project"),
+        attr("project_id", project_id.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Project { project_id } => to_binary(&query_project(deps, env, project_id)?),
+        QueryMsg::Projects { status, start_after, limit } => to_binary(&query_projects(deps, env, status, start_after, limit)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        curators: config.curators
+            .iter()
+            .map(|curator| Ok(deps.api.addr_humanize(curator)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+    })
+}
+
+fn humanize_option(api: &dyn Api, addr: Option<CanonicalAddr>) -> StdResult<Option<String>> {
+    addr.map(|addr| Ok(api.addr_humanize(&addr)?.to_string())).transpose()
+}
+
+fn project_response(deps: Deps, env: &Env, project_id: u64, project: Project) -> StdResult<ProjectResponse> {
+    let status = query_project_status(deps, env, &project.presale)?;
+    Ok(ProjectResponse {
+        project_id,
+        name: project.name,
+        logo_url: project.logo_url,
+        description: project.description,
+        socials: project.socials,
+        token: humanize_option(deps.api, project.token)?,
+        presale: deps.api.addr_humanize(&project.presale)?.to_string(),
+        vesting: humanize_option(deps.api, project.vesting)?,
+        staking: humanize_option(deps.api, project.staking)?,
+        locking: humanize_option(deps.api, project.locking)?,
+        status,
+    })
+}
+
+fn query_project(deps: Deps, env: Env, project_id: u64) -> StdResult<ProjectResponse> {
+    let project = PROJECTS.load(deps.storage, project_id)?;
+    project_response(deps, &env, project_id, project)
+}
+
+fn query_projects(deps: Deps, env: Env, status: Option<ProjectStatus>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ProjectsResponse> {
+    let (projects, last_project_id) = filtered_page(
+        deps.storage,
+        &PROJECTS,
+        start_after,
+        limit,
+        |project_id, project| project_response(deps, &env, project_id, project),
+        |project| status.is_none() || status.as_ref() == Some(&project.status),
+    )?;
+    Ok(ProjectsResponse { projects, last_project_id })
+}
@@ -0,0 +1,94 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{ProjectStatus, Social};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub curators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub logo_url: String,
+    pub description: String,
+    pub socials: Vec<Social>,
+    pub token: Option<String>,
+    pub presale: String,
+    pub vesting: Option<String>,
+    pub staking: Option<String>,
+    pub locking: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+    },
+    AddCurator {
+        curator: String,
+    },
+    RemoveCurator {
+        curator: String,
+    },
+    /// Lists an approved project, curators only.
+    RegisterProject {
+        project: ProjectInfo,
+    },
+    UpdateProject {
+        project_id: u64,
+        project: ProjectInfo,
+    },
+    RemoveProject {
+        project_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Project {
+        project_id: u64,
+    },
+    /// Projects in listing order with the given status when set, examining at most 30 projects per page.
+    Projects {
+        status: Option<ProjectStatus>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub curators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectResponse {
+    pub project_id: u64,
+    pub name: String,
+    pub logo_url: String,
+    pub description: String,
+    pub socials: Vec<Social>,
+    pub token: Option<String>,
+    pub presale: String,
+    pub vesting: Option<String>,
+    pub staking: Option<String>,
+    pub locking: Option<String>,
+    /// Lifecycle status derived from the presale at query time.
+    pub status: ProjectStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectsResponse {
+    pub projects: Vec<ProjectResponse>,
+    /// Last project examined, the start_after of the next page.
+    pub last_project_id: Option<u64>,
+}
@@ -0,0 +1,62 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::CanonicalAddr;
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    // Owner address, managing the curators.
+    pub owner: CanonicalAddr,
+    // Curators, allowed to list, update and remove projects.
+    pub curators: Vec<CanonicalAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Social {
+    // Platform name, e.g. twitter or telegram.
+    pub platform: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    // Presale not started yet
+    Upcoming,
+    // Presale rounds open
+    Live,
+    // Presale over, not finalized or failed or cancelled
+    Ended,
+    // Presale succeeded, sold tokens are distributed
+    Distributing,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Project {
+    // Project name.
+    pub name: String,
+    // Logo URL.
+    pub logo_url: String,
+    // Project description.
+    pub description: String,
+    // Social links.
+    pub socials: Vec<Social>,
+    // Project token.
+    pub token: Option<CanonicalAddr>,
+    // Presale contract, the source of the lifecycle status.
+    pub presale: CanonicalAddr,
+    // Vesting contract.
+    pub vesting: Option<CanonicalAddr>,
+    // Staking contract.
+    pub staking: Option<CanonicalAddr>,
+    // Locking contract.
+    pub locking: Option<CanonicalAddr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Projects by id, in listing order.
+pub const PROJECTS: Map<u64, Project> = Map::new("projects");
+
+pub const PROJECT_COUNT: Item<u64> = Item::new("project_count");
@@ -0,0 +1,238 @@
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, ProjectInfo, ProjectResponse, ProjectsResponse, QueryMsg};
+use crate::state::{ProjectStatus, Social};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_binary, to_binary, ContractResult, Env, OwnedDeps, SystemResult, Timestamp, Uint128, WasmQuery,
+};
+use cw2::{get_contract_version, set_contract_version};
+use pagination::MAX_LIMIT;
+use versioning::MigrationError;
+use presale::msg::{GetSaleStatusResponse, PresaleInfoResponse, QueryMsg as PresaleQueryMsg};
+use presale::state::{LeafFormat, SaleMode, SaleStatus};
+
+fn mock_project(name: &str, presale: &str) -> ProjectInfo {
+    ProjectInfo {
+        name: name.to_string(),
+        logo_url: "https://example.com/logo.png".to_string(),
+        description: "description".to_string(),
+        socials: vec![Social { platform: "twitter".to_string(), url: "https://twitter.com/project".to_string() }],
+        token: Some("token".to_string()),
+        presale: presale.to_string(),
+        vesting: Some("vesting".to_string()),
+        staking: None,
+        locking: None,
+    }
+}
+
+// Presales sell from 1000 to 2000, presale1 succeeded, presale2 was cancelled.
+fn mock_presales(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } => {
+            let res = match from_binary(msg).unwrap() {
+                PresaleQueryMsg::GetSaleStatus {} => to_binary(&GetSaleStatusResponse {
+                    sold_amount: Uint128::zero(),
+                    raised_amounts: vec![],
+                    status: match contract_addr.as_str() {
+                        "presale1" => SaleStatus::Succeeded,
+                        "presale2" => SaleStatus::Cancelled,
+                        _ => SaleStatus::Pending,
+                    },
+                    clearing_price: None,
+                }),
+                PresaleQueryMsg::PresaleInfo {} => to_binary(&PresaleInfoResponse {
+                    owner: "owner".to_string(),
+                    accuracy: Uint128::zero(),
+                    accepted_assets: vec![],
+                    reward_decimals: 6,
+                    start_time: 1000,
+                    end_time: 2000,
+                    total_rewards_amount: Uint128::zero(),
+                    soft_cap: Uint128::zero(),
+                    tranches: vec![],
+                    sale_mode: SaleMode::FixedPrice,
+                    vesting: None,
+                    claim_start_time: None,
+                    treasury: "treasury".to_string(),
+                    platform_fee_bps: 0,
+                    liquidity: None,
+                    provided_liquidity: vec![],
+                    staking_tiers: None,
+                    lock_requirement: None,
+                    cool_off: None,
+                    whitelist_leaf_format: LeafFormat::Plain,
+                    kyc_signer: None,
+                }),
+                _ => panic!("unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        }
+        _ => panic!("unexpected query"),
+    });
+}
+
+fn mock_env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn project_ids(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, time: u64, status: Option<ProjectStatus>) -> Vec<u64> {
+    let res = query(deps.as_ref(), mock_env_at(time), QueryMsg::Projects { status, start_after: None, limit: None }).unwrap();
+    let projects: ProjectsResponse = from_binary(&res).unwrap();
+    projects.projects.iter().map(|project| project.project_id).collect()
+}
+
+#[test]
+fn test_curators() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        curators: vec!["curator".to_string()],
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    // only the owner manages curators
+    let info = mock_info("curator", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AddCurator { curator: "curator2".to_string() });
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let info = mock_info("owner", &[]);
+    let _ = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::AddCurator { curator: "curator2".to_string() }).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::AddCurator { curator: "curator2".to_string() });
+    assert_eq!(res.unwrap_err(), ContractError::CuratorExists {});
+
+    let _ = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::RemoveCurator { curator: "curator".to_string() }).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RemoveCurator { curator: "curator".to_string() });
+    assert_eq!(res.unwrap_err(), ContractError::CuratorNotFound {});
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config, ConfigResponse {
+        owner: "owner".to_string(),
+        curators: vec!["curator2".to_string()],
+    });
+
+    // removed curators no longer list projects
+    mock_presales(&mut deps);
+    let info = mock_info("curator", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RegisterProject { project: mock_project("project", "presale0") });
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let info = mock_info("curator2", &[]);
+    let _ = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RegisterProject { project: mock_project("project", "presale0") }).unwrap();
+}
+
+#[test]
+fn test_projects() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        curators: vec!["curator".to_string()],
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+    mock_presales(&mut deps);
+
+    let info = mock_info("curator", &[]);
+    let res = execute(deps.as_mut(), mock_env_at(500), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("", "presale0") });
+    assert_eq!(res.unwrap_err(), ContractError::InvalidInput {});
+
+    // the status is derived from the presales
+    let _ = execute(deps.as_mut(), mock_env_at(500), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("first", "presale0") }).unwrap();
+    let _ = execute(deps.as_mut(), mock_env_at(500), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("second", "presale1") }).unwrap();
+    let _ = execute(deps.as_mut(), mock_env_at(500), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("third", "presale2") }).unwrap();
+    let _ = execute(deps.as_mut(), mock_env_at(500), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("fourth", "presale3") }).unwrap();
+
+    let res = query(deps.as_ref(), mock_env_at(500), QueryMsg::Project { project_id: 0 }).unwrap();
+    let project: ProjectResponse = from_binary(&res).unwrap();
+    assert_eq!(project, ProjectResponse {
+        project_id: 0,
+        name: "first".to_string(),
+        logo_url: "https://example.com/logo.png".to_string(),
+        description: "description".to_string(),
+        socials: vec![Social { platform: "twitter".to_string(), url: "https://twitter.com/project".to_string() }],
+        token: Some("token".to_string()),
+        presale: "presale0".to_string(),
+        vesting: Some("vesting".to_string()),
+        staking: None,
+        locking: None,
+        status: ProjectStatus::Upcoming,
+    });
+    assert_eq!(project_ids(&deps, 500, Some(ProjectStatus::Upcoming)), vec![0, 3]);
+    assert_eq!(project_ids(&deps, 500, Some(ProjectStatus::Distributing)), vec![1]);
+    assert_eq!(project_ids(&deps, 500, Some(ProjectStatus::Ended)), vec![2]);
+
+    // the status follows the presale schedule without any sync
+    assert_eq!(project_ids(&deps, 1500, Some(ProjectStatus::Live)), vec![0, 3]);
+
+    // presales accept deposits until their end time included
+    assert_eq!(project_ids(&deps, 2000, Some(ProjectStatus::Live)), vec![0, 3]);
+    assert_eq!(project_ids(&deps, 2001, Some(ProjectStatus::Ended)), vec![0, 2, 3]);
+
+    // only curators update and remove projects
+    let info = mock_info("anyone", &[]);
+    let res = execute(deps.as_mut(), mock_env_at(2000), info.clone(), ExecuteMsg::UpdateProject { project_id: 3, project: mock_project("fourth", "presale1") });
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+    let res = execute(deps.as_mut(), mock_env_at(2000), info, ExecuteMsg::RemoveProject { project_id: 2 });
+    assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+    let info = mock_info("curator", &[]);
+    let _ = execute(deps.as_mut(), mock_env_at(2000), info.clone(), ExecuteMsg::UpdateProject { project_id: 3, project: mock_project("fourth", "presale1") }).unwrap();
+    let res = execute(deps.as_mut(), mock_env_at(2000), info.clone(), ExecuteMsg::UpdateProject { project_id: 4, project: mock_project("fifth", "presale1") });
+    assert!(res.is_err());
+    let _ = execute(deps.as_mut(), mock_env_at(2000), info, ExecuteMsg::RemoveProject { project_id: 2 }).unwrap();
+
+    assert_eq!(project_ids(&deps, 2000, Some(ProjectStatus::Distributing)), vec![1, 3]);
+    assert_eq!(project_ids(&deps, 2000, None), vec![0, 1, 3]);
+
+    let res = query(deps.as_ref(), mock_env_at(2000), QueryMsg::Projects { status: None, start_after: Some(0), limit: Some(1) }).unwrap();
+    let projects: ProjectsResponse = from_binary(&res).unwrap();
+    assert_eq!(projects.projects.iter().map(|project| project.project_id).collect::<Vec<u64>>(), vec![1]);
+    assert_eq!(projects.last_project_id, Some(1));
+
+    // filtered pages continue after the last examined project
+    let msg = QueryMsg::Projects { status: Some(ProjectStatus::Distributing), start_after: None, limit: Some(1) };
+    let projects: ProjectsResponse = from_binary(&query(deps.as_ref(), mock_env_at(2000), msg).unwrap()).unwrap();
+    assert_eq!(projects.projects.iter().map(|project| project.project_id).collect::<Vec<u64>>(), vec![1]);
+    assert_eq!(projects.last_project_id, Some(1));
+    let msg = QueryMsg::Projects { status: Some(ProjectStatus::Distributing), start_after: projects.last_project_id, limit: Some(1) };
+    let projects: ProjectsResponse = from_binary(&query(deps.as_ref(), mock_env_at(2000), msg).unwrap()).unwrap();
+    assert_eq!(projects.projects.iter().map(|project| project.project_id).collect::<Vec<u64>>(), vec![3]);
+    assert_eq!(projects.last_project_id, Some(3));
+
+    // a filtered page examines a bounded window of projects
+    let info = mock_info("curator", &[]);
+    for _ in 0..MAX_LIMIT {
+        let _ = execute(deps.as_mut(), mock_env_at(2000), info.clone(), ExecuteMsg::RegisterProject { project: mock_project("ended", "presale2") }).unwrap();
+    }
+    let _ = execute(deps.as_mut(), mock_env_at(2000), info, ExecuteMsg::RegisterProject { project: mock_project("distributing", "presale1") }).unwrap();
+    let msg = QueryMsg::Projects { status: Some(ProjectStatus::Distributing), start_after: Some(3), limit: None };
+    let projects: ProjectsResponse = from_binary(&query(deps.as_ref(), mock_env_at(2000), msg).unwrap()).unwrap();
+    assert!(projects.projects.is_empty());
+    assert_eq!(projects.last_project_id, Some(3 + MAX_LIMIT as u64));
+    let msg = QueryMsg::Projects { status: Some(ProjectStatus::Distributing), start_after: projects.last_project_id, limit: None };
+    let projects: ProjectsResponse = from_binary(&query(deps.as_ref(), mock_env_at(2000), msg).unwrap()).unwrap();
+    assert_eq!(projects.projects.iter().map(|project| project.project_id).collect::<Vec<u64>>(), vec![4 + MAX_LIMIT as u64]);
+}
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        curators: vec![],
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+
+    let _ = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(get_contract_version(&deps.storage).unwrap().contract, "crates.io:registry");
+
+    set_contract_version(&mut deps.storage, "crates.io:presale", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::InvalidContract {}));
+
+    set_contract_version(&mut deps.storage, "crates.io:registry", "9.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(res.unwrap_err(), ContractError::Migration(MigrationError::CannotDowngrade {}));
+}
@@ -18,9 +18,15 @@ use crate::{
     }
 };
 
+use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use versioning::{assert_migration, migration_attributes};
 use std::collections::BTreeMap;
 
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:staking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -45,6 +51,7 @@ pub fn instantiate(
             global_reward_index: Decimal::zero(),
         },
     )?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default())
 }
@@ -463,6 +470,12 @@ pub fn assert_new_schedules(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION)))
 }
+
@@ -36,8 +36,7 @@ pub enum Cw20HookMsg {
     Bond {},
 }
 
-/// migrate struct for distribution schedule
-/// block-based schedule to a time-based schedule
+/// Migrations only update the cw2 contract version, refusing other contracts and downgrades.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
 
@@ -1,14 +1,16 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::mock_querier::mock_dependencies;
 use crate::msg::ExecuteMsg::UpdateConfig;
 use crate::msg::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse,
-    StateResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    StakerInfoResponse, StateResponse,
 };
+use crate::state::read_state;
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
     attr, from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
 };
+use cw2::{get_contract_version, set_contract_version, CONTRACT};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 #[test]
@@ -1035,3 +1037,60 @@ fn test_update_config() {
         ]
     );
 }
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:staking");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("previous_version", env!("CARGO_PKG_VERSION")),
+            attr("new_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+
+    // contracts deployed before versioning are already time-based, the schedule is kept
+    CONTRACT.remove(&mut deps.storage);
+    let state = read_state(&deps.storage).unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("previous_version", "none"),
+            attr("new_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.distribution_schedule, vec![(100, 200, Uint128::from(1000000u128))]);
+    assert_eq!(read_state(&deps.storage).unwrap(), state);
+
+    // downgrades are refused
+    set_contract_version(&mut deps.storage, "crates.io:staking", "99.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate to an older version"),
+        _ => panic!("Must return generic error"),
+    };
+
+    // so are migrations from another contract
+    set_contract_version(&mut deps.storage, "crates.io:vesting", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate from a different contract"),
+        _ => panic!("Must return generic error"),
+    };
+}
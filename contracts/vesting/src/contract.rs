@@ -1,10 +1,17 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, CosmosMsg, WasmMsg, Uint128};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, StdError, CosmosMsg, WasmMsg, Uint128};
+use cw_storage_plus::Bound;
+use cw2::set_contract_version;
 use cw20::Cw20ExecuteMsg;
+use versioning::{assert_migration, migration_attributes};
 
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, UsersCountResponse, GetUserResponse, GetUsersResponse, AmountResponse, MigrateMsg};
-use crate::state::{RECIPIENTS, UserInfo, State, STATE, ACCURACY};
+use crate::state::{RECIPIENTS, UserInfo, State, STATE, ACCURACY, DEFAULT_LIMIT, MAX_LIMIT};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:vesting";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -29,6 +36,7 @@ pub fn instantiate(
     };
 
     STATE.save(deps.storage, &state)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new())
 }
@@ -36,8 +44,13 @@ pub fn instantiate(
 /************************************ Migration *************************************/
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let previous_version = assert_migration(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attributes(migration_attributes(previous_version.as_ref(), CONTRACT_VERSION)))
 }
 
 /************************************ Execution *************************************/
@@ -54,6 +67,7 @@ pub fn execute(
         ExecuteMsg::SetWorker { worker } => execute_set_worker(deps, info, worker),
         ExecuteMsg::SetStartTime { new_start_time } => execute_set_start_time(deps, env, info, new_start_time),
         ExecuteMsg::UpdateRecipient { recp, amount } => execute_update_recipient(deps, env, info, recp, amount),
+        ExecuteMsg::ClearRecipients { start_after, limit } => execute_clear_recipients(deps, info, start_after, limit),
         ExecuteMsg::Withdraw {} => execute_withdraw(deps, env, info)
     }
 }
@@ -157,6 +171,37 @@ pub fn execute_update_recipient(deps: DepsMut, env: Env, info: MessageInfo, recp
     Ok(Response::new().add_attribute("method", "update_recipient"))
 }
 
+pub fn execute_clear_recipients(deps: DepsMut, info: MessageInfo, start_after: Option<String>, limit: Option<u32>) -> StdResult<Response> {
+    let mut state: State = STATE.load(deps.storage)?;
+
+    // permission check
+    let sender_canonical = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender_canonical != state.owner && sender_canonical != state.worker {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // recipients are cleared until they withdraw, also once vesting has started
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let recipients = RECIPIENTS
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(String, UserInfo)>>>()?;
+    for (recp, recp_info) in recipients.iter() {
+        if recp_info.withrawn_amount != 0 {
+            return Err(StdError::generic_err("already withdrawn"));
+        }
+        RECIPIENTS.remove(deps.storage, recp.clone());
+        state.total_vesting_amount -= recp_info.total_amount;
+    }
+    state.userlist.retain(|user| !recipients.iter().any(|(recp, _)| recp == user));
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "clear_recipients")
+        .add_attribute("cleared_count", recipients.len().to_string())
+        .add_attribute("remaining_count", state.userlist.len().to_string()))
+}
+
 pub fn execute_withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
     let state: State = STATE.load(deps.storage)?;
     let sender = info.sender.into_string();
@@ -30,6 +30,11 @@ pub enum ExecuteMsg {
         recp: String,
         amount: u64
     },
+    /// Removes a page of recipients in address order, as long as they have not withdrawn.
+    ClearRecipients {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     SetStartTime {
         new_start_time: u64
     },
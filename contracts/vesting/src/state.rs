@@ -48,3 +48,6 @@ pub const STATE: Item<State> = Item::new("state");
 pub const RECIPIENTS: Map<String, UserInfo> = Map::new("recipients");
 
 pub const ACCURACY: u64 = 1000;
+
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
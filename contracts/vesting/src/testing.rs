@@ -1,13 +1,14 @@
-use crate::contract::{execute, instantiate, query};
-use crate::msg::{InstantiateMsg, ExecuteMsg, QueryMsg, AmountResponse, UsersCountResponse, GetUserResponse, GetUsersResponse};
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::msg::{InstantiateMsg, ExecuteMsg, MigrateMsg, QueryMsg, AmountResponse, UsersCountResponse, GetUserResponse, GetUsersResponse};
 use cosmwasm_std::testing::{mock_env, mock_info, mock_dependencies};
 use cosmwasm_std::{
-    from_binary, StdError, Timestamp,
+    attr, from_binary, StdError, Timestamp,
 };
+use cw2::{get_contract_version, set_contract_version};
 
 #[test]
 fn test_initialize() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -25,7 +26,7 @@ fn test_initialize() {
 
 #[test]
 fn test_security() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -39,7 +40,7 @@ fn test_security() {
     let _ = instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
     let update_msg = ExecuteMsg::UpdateRecipient { recp: "user".to_string(), amount: 1000 };
-    let set_start_time_msg = ExecuteMsg::SetStartTime { new_start_time: 1000 };
+    let set_start_time_msg = ExecuteMsg::SetStartTime { new_start_time: mock_env().block.time.seconds() + 1000 };
     let transfer_ownership_msg = ExecuteMsg::TransferOwnerShip { new_owner: "user".to_string() };
 
     let res = execute(
@@ -82,7 +83,7 @@ fn test_security() {
 
 #[test]
 fn test_vesting_amount() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -100,7 +101,7 @@ fn test_vesting_amount() {
     let msg = ExecuteMsg::UpdateRecipient { recp: "user".to_string(), amount: user_vesting_amount };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-    let start_time: u64 = 1;
+    let start_time: u64 = mock_env().block.time.seconds() + 1;
 
     // set start time of vesting
     let msg = ExecuteMsg::SetStartTime { new_start_time: start_time };
@@ -174,7 +175,7 @@ fn test_vesting_amount() {
 #[test]
 fn test_query() {
     let env = mock_env();
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -213,7 +214,7 @@ fn test_query() {
 #[test]
 fn test_query_bulk() {
     let env = mock_env();
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -247,9 +248,79 @@ fn test_query_bulk() {
     }
 }
 
+#[test]
+fn test_clear_recipients() {
+    let env = mock_env();
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        reward_token: "reward_token".to_string(),
+        release_interval: 60,
+        release_rate: 100,
+        initial_unlock: 100,
+        lock_period: 600,
+        vesting_period: 6000,
+        distribution_amount: 1000000,
+    };
+    let info = mock_info("owner", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+    for user in ["user1", "user2", "user3", "user4"] {
+        let msg = ExecuteMsg::UpdateRecipient { recp: user.to_string(), amount: 1000 };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let clear_msg = |start_after: Option<&str>, limit| ExecuteMsg::ClearRecipients {
+        start_after: start_after.map(|user| user.to_string()),
+        limit,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), clear_msg(None, None));
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), clear_msg(None, Some(1))).unwrap();
+    assert_eq!(res.attributes[1], attr("cleared_count", "1"));
+    assert_eq!(res.attributes[2], attr("remaining_count", "3"));
+    let user_response: GetUserResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::GetUser { user: "user1".to_string() }).unwrap(),
+    ).unwrap();
+    assert_eq!(user_response.data.total_amount, 0);
+
+    // the cleared amount is available again
+    let msg = ExecuteMsg::UpdateRecipient { recp: "user1".to_string(), amount: 1000000 - 3000 };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // recipients can still be cleared in pages once vesting has started
+    let start_time = env.block.time.seconds() + 1;
+    let msg = ExecuteMsg::SetStartTime { new_start_time: start_time };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let mut env = env;
+    env.block.time = Timestamp::from_seconds(start_time + 10);
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), clear_msg(None, Some(2))).unwrap();
+    assert_eq!(res.attributes[1], attr("cleared_count", "2"));
+    assert_eq!(res.attributes[2], attr("remaining_count", "2"));
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), clear_msg(Some("user2"), Some(1))).unwrap();
+    assert_eq!(res.attributes[1], attr("cleared_count", "1"));
+    assert_eq!(res.attributes[2], attr("remaining_count", "1"));
+    let count_response: UsersCountResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::UsersCount {  }).unwrap(),
+    ).unwrap();
+    assert_eq!(count_response.count, 1);
+
+    // recipients who withdrew are kept
+    env.block.time = Timestamp::from_seconds(start_time + 600);
+    execute(deps.as_mut(), env.clone(), mock_info("user4", &[]), ExecuteMsg::Withdraw {}).unwrap();
+    let res = execute(deps.as_mut(), env, info, clear_msg(Some("user3"), None));
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "already withdrawn"),
+        _ => panic!("Must return already withdrawn error"),
+    }
+}
+
 #[test]
 fn test_withdraw() {
-    let mut deps = mock_dependencies(&[]);
+    let mut deps = mock_dependencies();
     let init_msg = InstantiateMsg {
         reward_token: "reward_token".to_string(),
         release_interval: 60,
@@ -267,7 +338,7 @@ fn test_withdraw() {
     let msg = ExecuteMsg::UpdateRecipient { recp: "user".to_string(), amount: user_vesting_amount };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-    let start_time: u64 = 1;
+    let start_time: u64 = mock_env().block.time.seconds() + 1;
 
     // set start time of vesting
     let msg = ExecuteMsg::SetStartTime { new_start_time: start_time };
@@ -306,3 +377,38 @@ fn test_withdraw() {
     assert_eq!(vested.amount, user_vesting_amount);
     assert_eq!(withdrawable.amount, user_vesting_amount - (amount_per_interval * 5 + initial_unlock_amount));
 }
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies();
+    let init_msg = InstantiateMsg {
+        reward_token: "reward_token".to_string(),
+        release_interval: 60,
+        release_rate: 10,
+        initial_unlock: 10,
+        lock_period: 600,
+        vesting_period: 6000,
+        distribution_amount: 1000000,
+    };
+    let info = mock_info("owner", &[]);
+    let _ = instantiate(deps.as_mut(), mock_env(), info, init_msg).unwrap();
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:vesting");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let _ = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    set_contract_version(&mut deps.storage, "crates.io:vesting", "99.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate to an older version"),
+        _ => panic!("Must return generic error"),
+    }
+
+    set_contract_version(&mut deps.storage, "crates.io:locking", "0.1.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "Cannot migrate from a different contract"),
+        _ => panic!("Must return generic error"),
+    }
+}
@@ -0,0 +1,72 @@
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
+
+/// Page of a listing keyed by id, built from the examined entries that pass `keep`.
+///
+/// Filters can reject any number of entries, so at most `MAX_LIMIT` entries are examined and a page
+/// can hold fewer than `limit` before the end of the listing. Returns the page and the last examined id,
+/// the `start_after` of the next page.
+pub fn filtered_page<T, R>(
+    storage: &dyn Storage,
+    map: &Map<u64, T>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    mut build: impl FnMut(u64, T) -> StdResult<R>,
+    keep: impl Fn(&R) -> bool,
+) -> StdResult<(Vec<R>, Option<u64>)>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let mut page = vec![];
+    let mut last_id = None;
+    for item in map.range(storage, start, None, Order::Ascending).take(MAX_LIMIT as usize) {
+        if page.len() == limit {
+            break;
+        }
+        let (id, entry) = item?;
+        last_id = Some(id);
+        let entry = build(id, entry)?;
+        if keep(&entry) {
+            page.push(entry);
+        }
+    }
+    Ok((page, last_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    const ENTRIES: Map<u64, u64> = Map::new("entries");
+
+    #[test]
+    fn test_filtered_page() {
+        let mut storage = MockStorage::new();
+        for id in 0..100u64 {
+            ENTRIES.save(&mut storage, id, &(id * 2)).unwrap();
+        }
+
+        // without a filter, pages hold up to the limit
+        let page = filtered_page(&storage, &ENTRIES, Some(3), Some(2), |_, value| Ok(value), |_| true).unwrap();
+        assert_eq!(page, (vec![8, 10], Some(5)));
+        let page = filtered_page(&storage, &ENTRIES, None, Some(100), |_, value| Ok(value), |_| true).unwrap();
+        assert_eq!(page.0.len(), MAX_LIMIT as usize);
+
+        // a filter stops at the limit or after the examined window
+        let page = filtered_page(&storage, &ENTRIES, None, Some(2), |_, value| Ok(value), |value| value % 10 == 0).unwrap();
+        assert_eq!(page, (vec![0, 10], Some(5)));
+        let page = filtered_page(&storage, &ENTRIES, Some(5), None, |_, value| Ok(value), |value| *value > 150).unwrap();
+        assert_eq!(page, (vec![], Some(5 + MAX_LIMIT as u64)));
+        let page = filtered_page(&storage, &ENTRIES, Some(90), None, |_, value| Ok(value), |value| *value > 150).unwrap();
+        assert_eq!(page, (vec![182, 184, 186, 188, 190, 192, 194, 196, 198], Some(99)));
+    }
+}
@@ -0,0 +1,91 @@
+use cosmwasm_std::{attr, Attribute, StdError, Storage};
+use cw2::CONTRACT;
+use thiserror::Error;
+
+pub use semver::Version;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrationError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Cannot migrate from a different contract")]
+    InvalidContract {},
+
+    #[error("Cannot migrate to an older version")]
+    CannotDowngrade {},
+
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+}
+
+impl From<MigrationError> for StdError {
+    fn from(err: MigrationError) -> Self {
+        match err {
+            MigrationError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
+
+/// Checks that the stored contract can be migrated to `contract_version`.
+/// Returns the stored version, none for contracts instantiated before versioning.
+pub fn assert_migration(
+    storage: &dyn Storage,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Option<Version>, MigrationError> {
+    let previous_version = match CONTRACT.may_load(storage)? {
+        Some(info) => {
+            if info.contract != contract_name {
+                return Err(MigrationError::InvalidContract {});
+            }
+            Some(parse_version(&info.version)?)
+        }
+        None => None,
+    };
+    let new_version = parse_version(contract_version)?;
+    if matches!(previous_version, Some(ref version) if *version > new_version) {
+        return Err(MigrationError::CannotDowngrade {});
+    }
+    Ok(previous_version)
+}
+
+/// Attributes of the migration response.
+pub fn migration_attributes(previous_version: Option<&Version>, contract_version: &str) -> Vec<Attribute> {
+    vec![
+        attr("previous_version", previous_version.map_or("none".to_string(), |version| version.to_string())),
+        attr("new_version", contract_version),
+    ]
+}
+
+pub fn parse_version(version: &str) -> Result<Version, MigrationError> {
+    Version::parse(version).map_err(|e| MigrationError::InvalidVersion(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cw2::set_contract_version;
+
+    #[test]
+    fn test_assert_migration() {
+        let mut storage = MockStorage::new();
+
+        // contracts instantiated before versioning have no version stored
+        assert_eq!(assert_migration(&storage, "crates.io:presale", "0.2.0"), Ok(None));
+
+        set_contract_version(&mut storage, "crates.io:presale", "0.1.0").unwrap();
+        assert_eq!(assert_migration(&storage, "crates.io:presale", "0.2.0"), Ok(Some(Version::new(0, 1, 0))));
+        assert_eq!(assert_migration(&storage, "crates.io:presale", "0.1.0"), Ok(Some(Version::new(0, 1, 0))));
+        assert_eq!(assert_migration(&storage, "crates.io:presale", "0.0.9"), Err(MigrationError::CannotDowngrade {}));
+        assert_eq!(assert_migration(&storage, "crates.io:vesting", "0.2.0"), Err(MigrationError::InvalidContract {}));
+        assert!(matches!(assert_migration(&storage, "crates.io:presale", "latest"), Err(MigrationError::InvalidVersion(_))));
+
+        assert_eq!(
+            StdError::from(MigrationError::CannotDowngrade {}),
+            StdError::generic_err("Cannot migrate to an older version")
+        );
+    }
+}
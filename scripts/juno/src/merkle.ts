@@ -0,0 +1,96 @@
+import { createHash } from "crypto";
+
+export const WHITELIST_LEAF_DOMAIN = "presale/whitelist/v1";
+
+export type LeafFormat = "plain" | "v1";
+
+export interface LeafContext {
+  chainId: string;
+  contract: string;
+  roundId: number;
+}
+
+const sha256 = (data: Buffer): Buffer =>
+  createHash("sha256").update(data).digest();
+
+// String field prefixed by its big-endian u32 byte length
+const prefixed = (field: string): Buffer => {
+  const bytes = Buffer.from(field, "utf8");
+  const length = Buffer.alloc(4);
+  length.writeUInt32BE(bytes.length);
+  return Buffer.concat([length, bytes]);
+};
+
+const u64 = (value: number): Buffer => {
+  const bytes = Buffer.alloc(8);
+  bytes.writeBigUInt64BE(BigInt(value));
+  return bytes;
+};
+
+const u128 = (value: string): Buffer =>
+  Buffer.from(BigInt(value).toString(16).padStart(32, "0"), "hex");
+
+/**
+ * @notice Whitelist leaf of an address and its allocation, matching the
+ * presale contract.
+ */
+export function whitelistLeaf(
+  format: LeafFormat,
+  ctx: LeafContext,
+  address: string,
+  allocation: string
+): Buffer {
+  if (format === "plain") {
+    return sha256(Buffer.from(`${address}${allocation}`, "utf8"));
+  }
+  return sha256(
+    Buffer.concat([
+      prefixed(WHITELIST_LEAF_DOMAIN),
+      prefixed(ctx.chainId),
+      prefixed(ctx.contract),
+      u64(ctx.roundId),
+      prefixed(address),
+      u128(allocation),
+    ])
+  );
+}
+
+// Pairs are hashed in sorted order, so proofs do not need to carry positions
+const hashPair = (a: Buffer, b: Buffer): Buffer =>
+  sha256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a]));
+
+/**
+ * @notice Builds the tree levels from the leaves up to the root. The last
+ * node of an odd level is carried up unchanged.
+ */
+export function buildTree(leaves: Buffer[]): Buffer[][] {
+  const levels = [leaves];
+  while (levels[levels.length - 1].length > 1) {
+    const level = levels[levels.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(i + 1 < level.length ? hashPair(level[i], level[i + 1]) : level[i]);
+    }
+    levels.push(next);
+  }
+  return levels;
+}
+
+export function merkleRoot(levels: Buffer[][]): string {
+  return levels[levels.length - 1][0].toString("hex");
+}
+
+/**
+ * @notice Hex-encoded proof of the leaf at the given index.
+ */
+export function merkleProof(levels: Buffer[][], index: number): string[] {
+  const proof: string[] = [];
+  for (const level of levels.slice(0, -1)) {
+    const sibling = index ^ 1;
+    if (sibling < level.length) {
+      proof.push(level[sibling].toString("hex"));
+    }
+    index = Math.floor(index / 2);
+  }
+  return proof;
+}
@@ -3,8 +3,14 @@ import "./constants";
 
 import { SigningCosmWasmClient } from "@cosmjs/cosmwasm-stargate";
 import { GasPrice } from "@cosmjs/stargate";
+import fs from "fs";
 import { instantiateContract } from "./util";
 import { lockingCodeId, presaleCodeId, vestingCodeId } from "./constants";
+import { buildTree, merkleRoot, whitelistLeaf } from "./merkle";
+
+// Whitelist of the private round, a JSON list of { address, allocation }
+const WHITELIST_PATH = process.env.WHITELIST_PATH || "whitelist.json";
+const PRIVATE_ROUND_ID = 0;
 
 async function setVestingWorker(
   client: SigningCosmWasmClient,
@@ -44,6 +50,27 @@ async function setVestingStartTime(
   );
 }
 
+async function setMerkleRoot(
+  client: SigningCosmWasmClient,
+  wallet: DirectSecp256k1HdWallet,
+  presale: string,
+  roundId: number,
+  root: string
+) {
+  const [account] = await wallet.getAccounts();
+  await client.execute(
+    account.address,
+    presale,
+    {
+      set_merkle_root: {
+        round_id: roundId,
+        merkle_root: root,
+      },
+    },
+    "auto"
+  );
+}
+
 async function deployContracts(
   client: SigningCosmWasmClient,
   wallet: DirectSecp256k1HdWallet
@@ -55,9 +82,12 @@ async function deployContracts(
   // Params need to care
   const rewardToken =
     "juno1dvzgy9tz200pstzem0tf0w24nsfmjxhzmyf9xqqafjpx4thrjapq6v572r";
-  const merkleRoot =
-    "b1e5f5709783df6791e6327458961c81ac685cc89e87803e4197d91a964254ee";
+  const whitelist: { address: string; allocation: string }[] = JSON.parse(
+    fs.readFileSync(WHITELIST_PATH, "utf8")
+  );
   const totalRewardsAmount = 125000000000;
+  const treasury = deployer.address;
+  const platformFeeBps = 0;
   const privateStart = Math.floor(
     new Date("2022-06-13T03:30:00.000Z").getTime() / 1000
   );
@@ -89,15 +119,52 @@ async function deployContracts(
   console.log("Vesting:", vesting.contractAddress);
 
   const presaleParams = {
-    fund_denom: "ujunox",
+    accepted_assets: [
+      {
+        info: { native: { denom: "ujunox" } },
+        exchange_rate: "800000", // ACCURACY: 100000000u128
+      },
+    ],
     reward_token: rewardToken,
     vesting: vesting.contractAddress,
-    whitelist_merkle_root: merkleRoot,
-    exchange_rate: "800000", // ACCURACY: 100000000u128
-    private_start_time: privateStart,
-    public_start_time: publicStart,
-    presale_period: 3600, // 1 hour
+    claim_start_time: null,
+    rounds: [
+      {
+        name: "private",
+        start_time: privateStart,
+        end_time: publicStart - 1,
+        price_multiplier: "1",
+        // v1 leaves are bound to the presale address, the root is set once it is known
+        whitelist_merkle_root: "",
+        allocation_type: "guaranteed",
+        max_allocation: null,
+        cap: null,
+      },
+      {
+        name: "public",
+        start_time: publicStart,
+        end_time: publicStart + 3600, // 1 hour
+        price_multiplier: "1",
+        whitelist_merkle_root: "",
+        allocation_type: "public",
+        max_allocation: null,
+        cap: null,
+      },
+    ],
     total_rewards_amount: totalRewardsAmount.toString(),
+    soft_cap: "0",
+    tranches: [],
+    sale_mode: "fixed_price",
+    treasury,
+    platform_fee_bps: platformFeeBps,
+    liquidity: null,
+    staking_tiers: null,
+    lock_requirement: null,
+    lottery: null,
+    cool_off: null,
+    whitelist_leaf_format: "v1",
+    whitelists: [],
+    kyc_signer: null,
   };
   const presale = await instantiateContract(
     client,
@@ -108,6 +175,20 @@ async function deployContracts(
   );
   console.log("Presale:", presale.contractAddress);
 
+  // the private round only opens at privateStart, after its root is set
+  const ctx = {
+    chainId: await client.getChainId(),
+    contract: presale.contractAddress,
+    roundId: PRIVATE_ROUND_ID,
+  };
+  const root = merkleRoot(
+    buildTree(
+      whitelist.map((e) => whitelistLeaf("v1", ctx, e.address, e.allocation))
+    )
+  );
+  await setMerkleRoot(client, wallet, presale.contractAddress, PRIVATE_ROUND_ID, root);
+  console.log("Whitelist root:", root);
+
   await setVestingWorker(
     client,
     wallet,
@@ -32,6 +32,8 @@ async function main() {
   await storeContract(client, wallet, "../../artifacts/presale.wasm");
   await storeContract(client, wallet, "../../artifacts/vesting.wasm");
   await storeContract(client, wallet, "../../artifacts/locking.wasm");
+  await storeContract(client, wallet, "../../artifacts/factory.wasm");
+  await storeContract(client, wallet, "../../artifacts/registry.wasm");
   await storeContract(client, wallet, "../../artifacts/cw20_base.wasm");
 }
 
@@ -0,0 +1,64 @@
+import fs from "fs";
+import path from "path";
+import {
+  buildTree,
+  LeafFormat,
+  merkleProof,
+  merkleRoot,
+  whitelistLeaf,
+} from "./merkle";
+
+// Test vectors shared with the presale contract tests
+const VECTORS_PATH = path.join(
+  __dirname,
+  "../../../contracts/presale/testdata/whitelist_vectors.json"
+);
+
+interface LeafVector {
+  format: LeafFormat;
+  chain_id: string;
+  contract: string;
+  round_id: number;
+  address: string;
+  allocation: string;
+  leaf: string;
+}
+
+interface TreeVector {
+  format: LeafFormat;
+  chain_id: string;
+  contract: string;
+  round_id: number;
+  entries: { address: string; allocation: string }[];
+  root: string;
+  proofs: { address: string; allocation: string; proof: string[] }[];
+}
+
+function check(name: string, actual: unknown, expected: unknown) {
+  if (JSON.stringify(actual) !== JSON.stringify(expected)) {
+    throw new Error(`${name}: expected ${expected}, got ${actual}`);
+  }
+}
+
+function main() {
+  const vectors = JSON.parse(fs.readFileSync(VECTORS_PATH, "utf8"));
+
+  vectors.leaves.forEach((v: LeafVector, i: number) => {
+    const ctx = { chainId: v.chain_id, contract: v.contract, roundId: v.round_id };
+    check(`leaf ${i}`, whitelistLeaf(v.format, ctx, v.address, v.allocation).toString("hex"), v.leaf);
+  });
+
+  vectors.trees.forEach((v: TreeVector, i: number) => {
+    const ctx = { chainId: v.chain_id, contract: v.contract, roundId: v.round_id };
+    const levels = buildTree(v.entries.map((e) => whitelistLeaf(v.format, ctx, e.address, e.allocation)));
+    check(`tree ${i} root`, merkleRoot(levels), v.root);
+    v.proofs.forEach((p) => {
+      const index = v.entries.findIndex((e) => e.address === p.address);
+      check(`tree ${i} proof of ${p.address}`, merkleProof(levels, index), p.proof);
+    });
+  });
+
+  console.log("Whitelist vectors match");
+}
+
+main();